@@ -0,0 +1,145 @@
+// https://en.wikipedia.org/wiki/Betting_in_poker#Limits
+// https://en.wikipedia.org/wiki/Betting_in_poker#All-in
+
+use std::fmt;
+
+use crate::simulation::Street;
+
+// Bets and raises allowed per street in fixed-limit games, including the opening bet or big blind
+pub const DEFAULT_RAISE_CAP: u32 = 4;
+
+// Betting situation of the player to act
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct BettingContext {
+    pub street: Street,
+    pub big_blind: u32,
+    // Highest bet on the street, and the size of the last full bet or raise
+    pub current_bet: u32,
+    pub min_raise: u32,
+    // Full bets and raises on the street so far, the big blind counts as the first preflop
+    pub raises: u32,
+    // All chips put in during the hand, including the bets on the current street
+    pub pot: u32,
+    // The player's bet on the street and the chips behind
+    pub bet: u32,
+    pub stack: u32,
+    // Players who have not folded
+    pub players: usize,
+}
+
+impl BettingContext {
+    pub fn to_call(&self) -> u32 {
+        self.current_bet.saturating_sub(self.bet)
+    }
+
+    pub fn all_in(&self) -> u32 {
+        self.bet + self.stack
+    }
+}
+
+/*
+    The rules for the size of bets and raises. Amounts are the total the player has put in
+    on the street after betting or raising, like Action::Bet and Action::Raise.
+*/
+pub trait BettingStructure: fmt::Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    // Size of a full bet or raise at the start of a street
+    fn opening_size(&self, _street: Street, big_blind: u32) -> u32 {
+        big_blind
+    }
+
+    // Smallest and largest total the player may bet or raise to, ignoring their stack
+    fn limits(&self, context: &BettingContext) -> Option<(u32, u32)>;
+
+    // Limits adjusted to the stack: a player who cannot afford the minimum may still go all-in for less
+    fn sizes(&self, context: &BettingContext) -> Option<(u32, u32)> {
+        let (min, max) = self.limits(context)?;
+        let all_in = context.all_in();
+
+        match all_in {
+            all_in if all_in <= context.current_bet => None,
+            all_in if all_in < min => Some((all_in, all_in)),
+            all_in => Some((min, max.min(all_in))),
+        }
+    }
+}
+
+// Bets and raises of at least the big blind and the last full raise, up to all-in
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct NoLimit;
+
+impl BettingStructure for NoLimit {
+    fn name(&self) -> &str {
+        "No-Limit"
+    }
+
+    fn limits(&self, context: &BettingContext) -> Option<(u32, u32)> {
+        Some((context.current_bet + context.min_raise, u32::MAX))
+    }
+}
+
+// Like no-limit, but at most raising by the size of the pot after calling
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct PotLimit;
+
+impl BettingStructure for PotLimit {
+    fn name(&self) -> &str {
+        "Pot-Limit"
+    }
+
+    fn limits(&self, context: &BettingContext) -> Option<(u32, u32)> {
+        let min = context.current_bet + context.min_raise;
+        let max = context.current_bet + context.pot + context.to_call();
+
+        Some((min, max.max(min)))
+    }
+}
+
+/*
+    Bets and raises of exactly the small bet preflop and on the flop, and the big bet on the
+    turn and river, with a cap on their number per street which is lifted heads-up.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct FixedLimit {
+    pub small_bet: u32,
+    pub big_bet: u32,
+    pub cap: u32,
+}
+
+impl FixedLimit {
+    // The usual structure, with the small bet equal to the big blind
+    pub fn new(big_blind: u32) -> Self {
+        Self {
+            small_bet: big_blind,
+            big_bet: big_blind * 2,
+            cap: DEFAULT_RAISE_CAP,
+        }
+    }
+
+    pub fn bet_size(&self, street: Street) -> u32 {
+        match street {
+            Street::Preflop | Street::Flop => self.small_bet,
+            Street::Turn | Street::River => self.big_bet,
+        }
+    }
+}
+
+impl BettingStructure for FixedLimit {
+    fn name(&self) -> &str {
+        "Fixed-Limit"
+    }
+
+    fn opening_size(&self, street: Street, _: u32) -> u32 {
+        self.bet_size(street)
+    }
+
+    fn limits(&self, context: &BettingContext) -> Option<(u32, u32)> {
+        if context.raises >= self.cap && context.players > 2 {
+            return None;
+        }
+
+        let amount = context.current_bet + self.bet_size(context.street);
+        Some((amount, amount))
+    }
+}
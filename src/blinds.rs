@@ -0,0 +1,112 @@
+// https://en.wikipedia.org/wiki/Blind_(poker)
+// https://en.wikipedia.org/wiki/Betting_in_poker#Antes
+// https://en.wikipedia.org/wiki/Button_(poker)
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum Ante {
+    #[default]
+    None,
+    // Posted by every player dealt in
+    Each(u32),
+    // Posted by the big blind for the whole table, after the big blind itself
+    BigBlind(u32),
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Blinds {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: Ante,
+    // Live straddle posted by the player left of the big blind, who then acts last preflop
+    pub straddle: Option<u32>,
+}
+
+impl Blinds {
+    pub fn new(small_blind: u32, big_blind: u32) -> Self {
+        Self {
+            small_blind,
+            big_blind,
+            ante: Ante::None,
+            straddle: None,
+        }
+    }
+}
+
+// Blinds a player owes after not being dealt in while they passed, posted when dealt in again
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct MissedBlinds {
+    // Posted dead, into the pot only
+    pub small_blind: bool,
+    // Posted live, counting towards the player's bet
+    pub big_blind: bool,
+}
+
+impl MissedBlinds {
+    pub fn any(&self) -> bool {
+        self.small_blind || self.big_blind
+    }
+}
+
+/*
+    Seats of the button and the blinds in a hand. The button and small blind may be dead,
+    i.e. belong to a seat which is empty or whose player busted, in which case no small blind
+    is posted.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Positions {
+    pub button: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+    pub straddle: Option<usize>,
+}
+
+impl Positions {
+    pub fn is_heads_up(&self) -> bool {
+        self.button == self.small_blind
+    }
+}
+
+/*
+    Positions for the next hand, given which seats have a player with chips. The big blind
+    moves forward to the next such player every hand, so nobody misses or pays it twice:
+    the small blind goes to the previous big blind and the button to the previous small
+    blind, even when those seats were vacated (dead button and dead small blind).
+
+    Heads-up the button posts the small blind and acts first preflop and last afterwards.
+    A straddle is only posted with at least three players. Needs two seats with chips.
+*/
+pub fn next_positions(previous: Option<Positions>, active: &[bool], straddle: bool) -> Positions {
+    let seat_count = active.len();
+    let next_active = |after: usize| (1..=seat_count).map(|offset| (after + offset) % seat_count).find(|&seat| active[seat]).unwrap();
+    let players = active.iter().filter(|&&active| active).count();
+
+    let mut positions = match previous {
+        Some(previous) if players == 2 => {
+            let big_blind = next_active(previous.big_blind);
+            let button = next_active(big_blind);
+            Positions { button, small_blind: button, big_blind, straddle: None }
+        }
+        None if players == 2 => {
+            let button = next_active(seat_count - 1);
+            Positions { button, small_blind: button, big_blind: next_active(button), straddle: None }
+        }
+        // Coming from heads-up the button simply moves on, as the blinds cannot follow it
+        Some(previous) if !previous.is_heads_up() && next_active(previous.big_blind) != previous.small_blind => Positions {
+            button: previous.small_blind,
+            small_blind: previous.big_blind,
+            big_blind: next_active(previous.big_blind),
+            straddle: None,
+        },
+        _ => {
+            let button = previous.map(|previous| next_active(previous.button)).unwrap_or_else(|| next_active(seat_count - 1));
+            let small_blind = next_active(button);
+            Positions { button, small_blind, big_blind: next_active(small_blind), straddle: None }
+        }
+    };
+
+    if straddle && players >= 3 {
+        positions.straddle = Some(next_active(positions.big_blind));
+    }
+
+    positions
+}
@@ -0,0 +1,270 @@
+// https://en.wikipedia.org/wiki/Texas_hold_%27em_starting_hands#Essentially_equivalent_hands
+// https://en.wikipedia.org/wiki/Poker_probability_(Texas_hold_%27em)#Starting_hands
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::{
+    card::{Card, Face, Suit},
+    range::Combo,
+};
+
+pub const STARTING_HAND_COUNT: usize = 169;
+pub const FLOP_CLASS_COUNT: usize = 1_755;
+
+// One of the 169 strategically distinct starting hands, e.g. "AKs", "QQ" or "T9o"
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
+pub struct StartingHand {
+    pub high: Face,
+    pub low: Face,
+    pub suited: bool,
+}
+
+impl StartingHand {
+    pub fn new(hole_cards: Combo) -> Self {
+        let (high, low) = if hole_cards.0 >= hole_cards.1 { hole_cards } else { (hole_cards.1, hole_cards.0) };
+
+        Self {
+            high: high.face,
+            low: low.face,
+            suited: high.suit == low.suit,
+        }
+    }
+
+    // All starting hands in grid order: AA, AKs, AQs, ..., AKo, KK, KQs, ..., 22
+    pub fn all() -> Vec<StartingHand> {
+        (0..STARTING_HAND_COUNT).map(StartingHand::from_index).collect()
+    }
+
+    /*
+        Position in the 13x13 grid (row * 13 + column), rows and columns ordered from Ace to Two:
+        pairs on the diagonal, suited hands above and offsuit hands below it.
+    */
+    pub fn index(&self) -> usize {
+        let high = Face::ALL.len() - (self.high as usize - 1);
+        let low = Face::ALL.len() - (self.low as usize - 1);
+
+        if self.suited {
+            high * Face::ALL.len() + low
+        } else {
+            low * Face::ALL.len() + high
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        let row = Face::ALL[index / Face::ALL.len()];
+        let column = Face::ALL[index % Face::ALL.len()];
+
+        Self {
+            high: row.max(column),
+            low: row.min(column),
+            suited: row > column,
+        }
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    // Number of hole card combos in this class
+    pub fn multiplicity(&self) -> usize {
+        match (self.is_pair(), self.suited) {
+            (true, _) => 6,
+            (false, true) => 4,
+            (false, false) => 12,
+        }
+    }
+
+    pub fn combos(&self) -> Vec<Combo> {
+        let mut combos = Vec::new();
+
+        for (i, &high_suit) in Suit::ALL.iter().enumerate() {
+            for (j, &low_suit) in Suit::ALL.iter().enumerate() {
+                let matches = match (self.is_pair(), self.suited) {
+                    (true, _) => i < j,
+                    (false, true) => i == j,
+                    (false, false) => i != j,
+                };
+
+                if matches {
+                    combos.push((Card { face: self.high, suit: high_suit }, Card { face: self.low, suit: low_suit }));
+                }
+            }
+        }
+
+        combos
+    }
+
+    // Canonical combo of this class, which is what canonical_suits() maps every member to
+    pub fn representative(&self) -> Combo {
+        let second_suit = if self.suited { Suit::Spades } else { Suit::Hearts };
+
+        (Card { face: self.high, suit: Suit::Spades }, Card { face: self.low, suit: second_suit })
+    }
+}
+
+impl fmt::Display for StartingHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.is_pair(), self.suited) {
+            (true, _) => write!(f, "{}{}", self.high, self.low),
+            (false, true) => write!(f, "{}{}s", self.high, self.low),
+            (false, false) => write!(f, "{}{}o", self.high, self.low),
+        }
+    }
+}
+
+impl FromStr for StartingHand {
+    type Err = ParseStartingHandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseStartingHandError(s.to_string());
+
+        let mut chars = s.chars();
+        let first = chars.next().and_then(Face::from_symbol).ok_or_else(error)?;
+        let second = chars.next().and_then(Face::from_symbol).ok_or_else(error)?;
+
+        let suited = match (first == second, chars.next()) {
+            (true, None) => false,
+            (false, Some('s')) => true,
+            (false, Some('o')) => false,
+            _ => return Err(error()),
+        };
+
+        if chars.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(Self {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseStartingHandError(pub String);
+
+impl fmt::Display for ParseStartingHandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid starting hand '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseStartingHandError {}
+
+/*
+    Relabel suits so that isomorphic card groups (e.g. hole cards and board) become identical.
+    Cards within a group are unordered and returned sorted from high to low, the order of the
+    groups matters. Of all 24 suit permutations the one yielding the highest card indices wins,
+    so the first suit seen becomes Spades, the next one Hearts and so on.
+*/
+pub fn canonical_suits(groups: &[&[Card]]) -> Vec<Vec<Card>> {
+    let key = |groups: &[Vec<Card>]| -> Vec<Vec<usize>> {
+        groups.iter().map(|group| group.iter().map(Card::index).collect()).collect()
+    };
+
+    let mut best: Option<Vec<Vec<Card>>> = None;
+
+    for permutation in suit_permutations() {
+        let mapped: Vec<Vec<Card>> = groups
+            .iter()
+            .map(|group| {
+                let mut cards: Vec<Card> = group
+                    .iter()
+                    .map(|card| Card {
+                        face: card.face,
+                        suit: permute(card.suit, &permutation),
+                    })
+                    .collect();
+                cards.sort_by_key(|card| std::cmp::Reverse(card.index()));
+                cards
+            })
+            .collect();
+
+        if best.as_ref().map(|best| key(&mapped) > key(best)).unwrap_or(true) {
+            best = Some(mapped);
+        }
+    }
+
+    best.unwrap()
+}
+
+// Number of distinct card groups isomorphic to the given ones, e.g. 4 for AKs and 12 for AKo
+pub fn suit_multiplicity(groups: &[&[Card]]) -> usize {
+    let mut images: Vec<Vec<Vec<usize>>> = suit_permutations()
+        .into_iter()
+        .map(|permutation| {
+            groups
+                .iter()
+                .map(|group| {
+                    let mut indices: Vec<usize> = group
+                        .iter()
+                        .map(|card| Card { face: card.face, suit: permute(card.suit, &permutation) }.index())
+                        .collect();
+                    indices.sort();
+                    indices
+                })
+                .collect()
+        })
+        .collect();
+
+    images.sort();
+    images.dedup();
+    images.len()
+}
+
+pub fn canonical_board(board: &[Card]) -> Vec<Card> {
+    canonical_suits(&[board]).remove(0)
+}
+
+// All 1,755 suit-isomorphic flop classes with the number of the 22,100 flops in each
+pub fn canonical_flops() -> Vec<([Card; 3], usize)> {
+    let mut classes: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+
+    for first in 0..52 {
+        for second in 0..first {
+            for third in 0..second {
+                let flop = [Card::from_index(first), Card::from_index(second), Card::from_index(third)];
+                let canonical = canonical_board(&flop);
+
+                *classes.entry(canonical.iter().map(Card::index).collect()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    classes
+        .into_iter()
+        .rev()
+        .map(|(indices, multiplicity)| {
+            (
+                [Card::from_index(indices[0]), Card::from_index(indices[1]), Card::from_index(indices[2])],
+                multiplicity,
+            )
+        })
+        .collect()
+}
+
+fn permute(suit: Suit, permutation: &[Suit; 4]) -> Suit {
+    permutation[Suit::ALL.iter().position(|&other| other == suit).unwrap()]
+}
+
+fn suit_permutations() -> Vec<[Suit; 4]> {
+    let mut permutations = Vec::new();
+
+    for a in Suit::ALL {
+        for b in Suit::ALL {
+            for c in Suit::ALL {
+                for d in Suit::ALL {
+                    let permutation = [a, b, c, d];
+                    let distinct = (0..4).all(|i| (0..i).all(|j| permutation[i] != permutation[j]));
+
+                    if distinct {
+                        permutations.push(permutation);
+                    }
+                }
+            }
+        }
+    }
+
+    permutations
+}
@@ -4,7 +4,7 @@
 // https://en.wikipedia.org/wiki/French-suited_playing_cards
 // https://en.wikipedia.org/wiki/Playing_cards_in_Unicode
 
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Card {
@@ -29,8 +29,52 @@ impl Card {
 
         char::from_u32(codepoint).unwrap()
     }
+
+    // Unique index 0..52, ordered by face first and suit second
+    pub fn index(&self) -> usize {
+        (self.face as usize - 2) * 4 + (self.suit as usize - 1)
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Card {
+            face: Face::ALL[Face::ALL.len() - 1 - index / 4],
+            suit: Suit::ALL[Suit::ALL.len() - 1 - index % 4],
+        }
+    }
+
+    // Card equality only compares faces, this compares face and suit
+    pub fn is_same(&self, other: &Card) -> bool {
+        self.face == other.face && self.suit == other.suit
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(face), Some(suit), None) => match (Face::from_symbol(face), Suit::from_abbr(suit)) {
+                (Some(face), Some(suit)) => Ok(Card { face, suit }),
+                _ => Err(ParseCardError(s.to_string())),
+            },
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseCardError(pub String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid card '{}'", self.0)
+    }
 }
 
+impl std::error::Error for ParseCardError {}
+
 impl PartialEq for Card {
     fn eq(&self, other: &Self) -> bool {
         self.face == other.face
@@ -77,6 +121,26 @@ pub enum Face {
 }
 
 impl Face {
+    pub const ALL: [Face; 13] = [
+        Face::Ace,
+        Face::King,
+        Face::Queen,
+        Face::Jack,
+        Face::Ten,
+        Face::Nine,
+        Face::Eight,
+        Face::Seven,
+        Face::Six,
+        Face::Five,
+        Face::Four,
+        Face::Three,
+        Face::Two,
+    ];
+
+    pub fn from_symbol(symbol: char) -> Option<Face> {
+        Face::ALL.into_iter().find(|face| face.symbol() == symbol.to_ascii_uppercase())
+    }
+
     #[rustfmt::skip]
     pub fn name(&self) -> &str {
         match self {
@@ -139,6 +203,12 @@ pub enum Suit {
 }
 
 impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    pub fn from_abbr(abbr: char) -> Option<Suit> {
+        Suit::ALL.into_iter().find(|suit| suit.abbr().starts_with(abbr.to_ascii_lowercase()))
+    }
+
     #[rustfmt::skip]
     pub fn name(&self) -> &str {
         match self {
@@ -0,0 +1,255 @@
+// https://en.wikipedia.org/wiki/Cash_game
+// https://en.wikipedia.org/wiki/Table_stakes
+
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{
+    blinds::Blinds,
+    table::{Action, GameError, Phase, Player, Table},
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum CashGameError {
+    BuyInLimits { min: u32, max: u32 },
+    BuyIn { amount: u32, min: u32, max: u32 },
+    NameTaken(String),
+    Game(GameError),
+}
+
+impl fmt::Display for CashGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CashGameError::BuyInLimits { min, max } => write!(f, "minimum buy-in {min} must be positive and at most the maximum {max}"),
+            CashGameError::BuyIn { amount, min, max } => write!(f, "buy-in of {amount} is outside of {min} to {max}"),
+            CashGameError::NameTaken(name) => write!(f, "a player named {name} is already seated"),
+            CashGameError::Game(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for CashGameError {}
+
+impl From<GameError> for CashGameError {
+    fn from(error: GameError) -> Self {
+        CashGameError::Game(error)
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct CashGameConfig {
+    pub seats: usize,
+    pub blinds: Blinds,
+    // Limits on the stack a player sits down with, and on topping up to
+    pub min_buy_in: u32,
+    pub max_buy_in: u32,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Movement {
+    BuyIn(u32),
+    // Buying in again after losing the whole stack
+    Rebuy(u32),
+    TopUp(u32),
+    // Chips won (positive) or lost in a hand, including blinds and antes
+    Hand { hand: u32, net: i64 },
+    CashOut(u32),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LedgerEntry {
+    pub player: String,
+    pub movement: Movement,
+}
+
+// Totals of a player's chip movements, the stack at the table is bought_in + won - cashed_out
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct Balance {
+    pub bought_in: u32,
+    pub won: i64,
+    pub cashed_out: u32,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Ledger {
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    // Every chip movement in the order it happened
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    pub fn player<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a LedgerEntry> + 'a {
+        self.entries.iter().filter(move |entry| entry.player == name)
+    }
+
+    pub fn balance(&self, name: &str) -> Balance {
+        self.player(name).fold(Balance::default(), |mut balance, entry| {
+            match entry.movement {
+                Movement::BuyIn(amount) | Movement::Rebuy(amount) | Movement::TopUp(amount) => balance.bought_in += amount,
+                Movement::Hand { net, .. } => balance.won += net,
+                Movement::CashOut(amount) => balance.cashed_out += amount,
+            }
+            balance
+        })
+    }
+
+    // Names of all players who took part in the session, in order of their first entry
+    pub fn players(&self) -> Vec<&str> {
+        let mut players: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !players.contains(&entry.player.as_str()) {
+                players.push(&entry.player);
+            }
+        }
+        players
+    }
+
+    fn record(&mut self, player: &str, movement: Movement) {
+        self.entries.push(LedgerEntry { player: player.to_string(), movement });
+    }
+}
+
+/*
+    A cash game session at one table, where players come and go between hands: they buy in
+    within the limits, rebuy or top up, sit out and back in (posting the blinds they missed)
+    and leave with their stack. Every chip movement is recorded in the ledger.
+*/
+#[derive(Debug)]
+pub struct CashGame {
+    table: Table,
+    min_buy_in: u32,
+    max_buy_in: u32,
+    hands: u32,
+    ledger: Ledger,
+}
+
+impl CashGame {
+    pub fn new(config: CashGameConfig) -> Result<Self, CashGameError> {
+        if config.min_buy_in == 0 || config.min_buy_in > config.max_buy_in {
+            return Err(CashGameError::BuyInLimits { min: config.min_buy_in, max: config.max_buy_in });
+        }
+
+        Ok(Self {
+            table: Table::new(config.seats, config.blinds)?,
+            min_buy_in: config.min_buy_in,
+            max_buy_in: config.max_buy_in,
+            hands: 0,
+            ledger: Ledger::default(),
+        })
+    }
+
+    // The table, for legal actions and the state of the current hand
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    pub fn hands_played(&self) -> u32 {
+        self.hands
+    }
+
+    // Takes a seat with a stack within the buy-in limits, joining mid-session owes a big blind
+    pub fn join(&mut self, seat: usize, name: &str, buy_in: u32) -> Result<(), CashGameError> {
+        if self.table.seats().any(|(_, seat)| seat.player.name == name) {
+            return Err(CashGameError::NameTaken(name.to_string()));
+        }
+
+        self.check_buy_in(buy_in)?;
+        self.table.sit(seat, Player::new(name), buy_in)?;
+        self.ledger.record(name, Movement::BuyIn(buy_in));
+
+        Ok(())
+    }
+
+    /*
+        Add chips between hands: a rebuy within the buy-in limits for a player who lost their
+        stack, otherwise a top-up up to the maximum buy-in.
+    */
+    pub fn add_chips(&mut self, seat: usize, amount: u32) -> Result<(), CashGameError> {
+        let stack = self.table.seat(seat).ok_or(GameError::SeatEmpty(seat))?.stack;
+
+        let movement = match stack {
+            0 => {
+                self.check_buy_in(amount)?;
+                Movement::Rebuy(amount)
+            }
+            stack if amount == 0 || stack.checked_add(amount).is_none_or(|total| total > self.max_buy_in) => {
+                return Err(CashGameError::BuyIn { amount, min: 1, max: self.max_buy_in.saturating_sub(stack) });
+            }
+            _ => Movement::TopUp(amount),
+        };
+
+        self.table.add_chips(seat, amount)?;
+        let name = self.table.seat(seat).unwrap().player.name.clone();
+        self.ledger.record(&name, movement);
+
+        Ok(())
+    }
+
+    pub fn sit_out(&mut self, seat: usize) -> Result<(), CashGameError> {
+        Ok(self.table.sit_out(seat)?)
+    }
+
+    pub fn sit_in(&mut self, seat: usize) -> Result<(), CashGameError> {
+        Ok(self.table.sit_in(seat)?)
+    }
+
+    // Leave the table between hands, cashing out the stack
+    pub fn leave(&mut self, seat: usize) -> Result<u32, CashGameError> {
+        let seat = self.table.leave(seat)?;
+        self.ledger.record(&seat.player.name, Movement::CashOut(seat.stack));
+
+        Ok(seat.stack)
+    }
+
+    pub fn start_hand<R: Rng>(&mut self, rng: &mut R) -> Result<(), CashGameError> {
+        self.table.start_hand(rng)?;
+        self.check_hand_over();
+
+        Ok(())
+    }
+
+    pub fn act(&mut self, action: Action) -> Result<(), CashGameError> {
+        self.table.act(action)?;
+        self.check_hand_over();
+
+        Ok(())
+    }
+
+    fn check_buy_in(&self, amount: u32) -> Result<(), CashGameError> {
+        match (self.min_buy_in..=self.max_buy_in).contains(&amount) {
+            true => Ok(()),
+            false => Err(CashGameError::BuyIn { amount, min: self.min_buy_in, max: self.max_buy_in }),
+        }
+    }
+
+    fn check_hand_over(&mut self) {
+        if self.table.phase() != Phase::Complete {
+            return;
+        }
+
+        self.hands += 1;
+
+        let result = self.table.result().unwrap();
+        let nets: Vec<(String, i64)> = self
+            .table
+            .seats()
+            .filter(|(_, seat)| seat.hole_cards.is_some() || seat.contributed > 0)
+            .map(|(i, seat)| {
+                let won = result.winnings.iter().find(|(winner, _)| *winner == i).map(|(_, amount)| *amount).unwrap_or(0);
+                (seat.player.name.clone(), won as i64 - seat.contributed as i64)
+            })
+            .collect();
+
+        for (name, net) in nets {
+            self.ledger.record(&name, Movement::Hand { hand: self.hands, net });
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{seq::SliceRandom, thread_rng, Rng};
 
 use crate::card::{Card, Face, Suit};
 
@@ -12,22 +12,8 @@ impl Deck {
     pub fn new() -> Self {
         let mut cards = Vec::new();
 
-        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-            for face in [
-                Face::Ace,
-                Face::King,
-                Face::Queen,
-                Face::Jack,
-                Face::Ten,
-                Face::Nine,
-                Face::Eight,
-                Face::Seven,
-                Face::Six,
-                Face::Five,
-                Face::Four,
-                Face::Three,
-                Face::Two,
-            ] {
+        for suit in Suit::ALL {
+            for face in Face::ALL {
                 cards.push(Card { face, suit });
             }
         }
@@ -52,6 +38,24 @@ impl Deck {
         self.cards.shuffle(&mut rng);
     }
 
+    pub fn shuffle_with<R: Rng>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
+    /*
+        Randomly pick the given number of cards without removing them, so the deck can be
+        reused for the next deal. Only the picked cards are shuffled, which is uniform no
+        matter in which order previous deals left the deck.
+    */
+    pub fn deal_random<R: Rng>(&mut self, rng: &mut R, amount: usize) -> &[Card] {
+        self.cards.partial_shuffle(rng, amount).0
+    }
+
+    // Take cards out of the deck, e.g. known or dead cards
+    pub fn remove(&mut self, cards: &[Card]) {
+        self.cards.retain(|card| !cards.iter().any(|other| other.is_same(card)));
+    }
+
     pub fn pop(&mut self) -> Option<Card> {
         self.cards.pop()
     }
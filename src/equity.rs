@@ -0,0 +1,875 @@
+// https://en.wikipedia.org/wiki/Poker_calculator
+// https://en.wikipedia.org/wiki/Monte_Carlo_method
+
+use std::{collections::HashMap, fmt, time::Instant};
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, seq::SliceRandom, thread_rng};
+use rayon::prelude::*;
+
+use crate::{
+    canonical::canonical_suits,
+    card::Card,
+    evaluator::{self, CardSet, HandRank, HandState},
+    game_logic::{Hand, Ranking, Strength},
+    range::{Combo, HandRange},
+    statistics::{self, StopCondition},
+};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 10;
+
+// Enumerate all remaining boards if there are at most this many, otherwise sample
+pub const EXACT_BOARD_LIMIT: u64 = 2_000_000;
+
+// Boards sampled between two checks of an adaptive stop condition
+pub const ADAPTIVE_BATCH_SIZE: u32 = 20_000;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Method {
+    Auto { iterations: u32 },
+    Exact,
+    MonteCarlo { iterations: u32 },
+    // Sample until the stop condition is met or max_iterations boards were sampled
+    Adaptive { stop: StopCondition, max_iterations: u32, stratified: bool },
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum EquityError {
+    PlayerCount(usize),
+    BoardLength(usize),
+    DuplicateCard(Card),
+    EmptyRange(usize),
+    ConflictingRanges,
+    StratifiedRanges,
+    NotEnoughCards { needed: usize, left: usize },
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::PlayerCount(count) => {
+                write!(f, "{count} players given, expected {MIN_PLAYERS} to {MAX_PLAYERS}")
+            }
+            EquityError::BoardLength(length) => write!(f, "board has {length} cards, expected at most 5"),
+            EquityError::DuplicateCard(card) => write!(f, "card {card} is used more than once"),
+            EquityError::EmptyRange(player) => write!(f, "range of player {player} has no combos left after card removal"),
+            EquityError::ConflictingRanges => write!(f, "ranges share all of their cards, no deal is possible"),
+            EquityError::NotEnoughCards { needed, left } => write!(f, "{needed} cards are needed for the hands and board, only {left} are left"),
+            EquityError::StratifiedRanges => write!(f, "stratified sampling is only supported for hole cards, not ranges"),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {}
+
+#[derive(Clone, Debug, Default)]
+pub struct PlayerEquity {
+    pub wins: u64,
+    pub ties: u64,
+    // Sum of pot shares, one per win and 1/n per n-way tie
+    pub share: f64,
+    pub share_squares: f64,
+    pub win_rankings: HashMap<Ranking, u64>,
+}
+
+impl PlayerEquity {
+    fn merge(&mut self, other: PlayerEquity) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.share += other.share;
+        self.share_squares += other.share_squares;
+
+        for (ranking, count) in other.win_rankings {
+            *self.win_rankings.entry(ranking).or_insert(0) += count;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EquityResult {
+    pub players: Vec<PlayerEquity>,
+    pub boards: u64,
+    pub exact: bool,
+    // Largest standard error of the players' equities (as a fraction), zero if exact
+    pub standard_error: f64,
+}
+
+impl EquityResult {
+    fn new(players: usize, exact: bool) -> Self {
+        Self {
+            players: vec![PlayerEquity::default(); players],
+            boards: 0,
+            exact,
+            standard_error: 0.,
+        }
+    }
+
+    fn merge(mut self, other: EquityResult) -> Self {
+        self.boards += other.boards;
+
+        for (player, other) in self.players.iter_mut().zip(other.players) {
+            player.merge(other);
+        }
+
+        self
+    }
+
+    pub fn win_percentage(&self, player: usize) -> f64 {
+        self.percentage(self.players[player].wins as f64)
+    }
+
+    pub fn tie_percentage(&self, player: usize) -> f64 {
+        self.percentage(self.players[player].ties as f64)
+    }
+
+    pub fn equity_percentage(&self, player: usize) -> f64 {
+        self.percentage(self.players[player].share)
+    }
+
+    fn percentage(&self, value: f64) -> f64 {
+        match self.boards {
+            0 => 0.,
+            boards => value / boards as f64 * 100.,
+        }
+    }
+
+    // Score one complete board, counted as many boards as its weight
+    fn showdown(&mut self, hole_cards: &[Combo], board: &[Card], weight: u64) {
+        self.score(&board_ranks(hole_cards, board)[..hole_cards.len()], weight);
+    }
+
+    fn score(&mut self, ranks: &[HandRank], weight: u64) {
+        let best = *ranks.iter().max().unwrap();
+        let winners = ranks.iter().filter(|&&rank| rank == best).count();
+
+        self.boards += weight;
+
+        for (player, _) in self.players.iter_mut().zip(ranks).filter(|(_, &rank)| rank == best) {
+            if winners == 1 {
+                player.wins += weight;
+                player.share += weight as f64;
+                player.share_squares += weight as f64;
+                *player.win_rankings.entry(best.ranking()).or_insert(0) += weight;
+            } else {
+                let share = 1. / winners as f64;
+
+                player.ties += weight;
+                player.share += weight as f64 * share;
+                player.share_squares += weight as f64 * share * share;
+            }
+        }
+    }
+
+    // Standard error of the players' mean pot shares, for independently sampled boards
+    fn sampling_error(&self) -> f64 {
+        self.players
+            .iter()
+            .map(|player| statistics::mean_standard_error(player.share, player.share_squares, self.boards as f64))
+            .fold(0., f64::max)
+    }
+}
+
+/*
+    Equity of 2 to 10 players' hole cards, given an optional partial board and dead cards.
+    With Method::Auto all remaining boards are enumerated when there are at most
+    EXACT_BOARD_LIMIT of them, otherwise the given number of boards is sampled.
+*/
+pub fn calculate(hole_cards: &[Combo], board: &[Card], dead_cards: &[Card], method: Method) -> Result<EquityResult, EquityError> {
+    if hole_cards.len() < MIN_PLAYERS || hole_cards.len() > MAX_PLAYERS {
+        return Err(EquityError::PlayerCount(hole_cards.len()));
+    }
+
+    if board.len() > 5 {
+        return Err(EquityError::BoardLength(board.len()));
+    }
+
+    let known: Vec<Card> = hole_cards
+        .iter()
+        .flat_map(|&(first, second)| [first, second])
+        .chain(board.iter().copied())
+        .chain(dead_cards.iter().copied())
+        .collect();
+
+    check_duplicates(&known)?;
+
+    let remaining = remaining_cards(&known);
+    let missing = 5 - board.len();
+
+    let exact = match method {
+        Method::Auto { .. } => binomial(remaining.len() as u64, missing as u64) <= EXACT_BOARD_LIMIT,
+        Method::Exact => true,
+        Method::MonteCarlo { .. } | Method::Adaptive { .. } => false,
+    };
+
+    Ok(match (exact, method) {
+        (true, _) => enumerate(hole_cards, board, &remaining, missing),
+        (false, Method::Auto { iterations } | Method::MonteCarlo { iterations }) => {
+            let mut result = sample(hole_cards, board, &remaining, missing, iterations);
+            result.standard_error = result.sampling_error();
+            result
+        }
+        (false, Method::Adaptive { stop, max_iterations, stratified }) if stratified && board.is_empty() => {
+            sample_stratified(hole_cards, &remaining, stop, max_iterations)
+        }
+        (false, Method::Adaptive { stop, max_iterations, .. }) => {
+            sample_adaptive(hole_cards, board, &remaining, missing, stop, max_iterations)
+        }
+        (false, Method::Exact) => unreachable!(),
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct ComboEquity {
+    pub combo: Combo,
+    pub weight: f32,
+    pub equity: f64,
+    // Weighted number of boards the combo was evaluated on
+    pub boards: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct RangeEquityResult {
+    pub equities: Vec<f64>,
+    pub combos: Vec<Vec<ComboEquity>>,
+    pub boards: u64,
+    pub exact: bool,
+    // Largest standard error of the players' equities (as a fraction), zero if exact
+    pub standard_error: f64,
+}
+
+impl RangeEquityResult {
+    pub fn equity_percentage(&self, player: usize) -> f64 {
+        self.equities[player] * 100.
+    }
+}
+
+// Weighted pot shares per player and combo (keyed by card indices), plus overall totals
+#[derive(Clone, Debug)]
+struct RangeTally {
+    combos: Vec<HashMap<(usize, usize), (f64, f64)>>,
+    shares: Vec<f64>,
+    share_squares: Vec<f64>,
+    weight: f64,
+    boards: u64,
+}
+
+impl RangeTally {
+    fn new(players: usize) -> Self {
+        Self {
+            combos: vec![HashMap::new(); players],
+            shares: vec![0.; players],
+            share_squares: vec![0.; players],
+            weight: 0.,
+            boards: 0,
+        }
+    }
+
+    fn merge(mut self, other: RangeTally) -> Self {
+        for (combos, other) in self.combos.iter_mut().zip(other.combos) {
+            for (key, (share, weight)) in other {
+                let entry = combos.entry(key).or_insert((0., 0.));
+                entry.0 += share;
+                entry.1 += weight;
+            }
+        }
+
+        for (share, other) in self.shares.iter_mut().zip(other.shares) {
+            *share += other;
+        }
+
+        for (square, other) in self.share_squares.iter_mut().zip(other.share_squares) {
+            *square += other;
+        }
+
+        self.weight += other.weight;
+        self.boards += other.boards;
+
+        self
+    }
+
+    fn showdown(&mut self, hole_cards: &[Combo], board: &[Card], weight: f64) {
+        self.score(hole_cards, &board_ranks(hole_cards, board)[..hole_cards.len()], weight);
+    }
+
+    fn score(&mut self, hole_cards: &[Combo], ranks: &[HandRank], weight: f64) {
+        let best = *ranks.iter().max().unwrap();
+        let winners = ranks.iter().filter(|&&rank| rank == best).count();
+
+        for (player, (hole_cards, &rank)) in hole_cards.iter().zip(ranks).enumerate() {
+            let share = match rank == best {
+                true => weight / winners as f64,
+                false => 0.,
+            };
+
+            let entry = self.combos[player].entry(combo_key(hole_cards)).or_insert((0., 0.));
+            entry.0 += share;
+            entry.1 += weight;
+
+            self.shares[player] += share;
+            self.share_squares[player] += share * share;
+        }
+
+        self.weight += weight;
+        self.boards += 1;
+    }
+
+    // Standard error of the players' mean pot shares, for independently sampled deals of weight one
+    fn sampling_error(&self) -> f64 {
+        self.shares
+            .iter()
+            .zip(&self.share_squares)
+            .map(|(&share, &squares)| statistics::mean_standard_error(share, squares, self.boards as f64))
+            .fold(0., f64::max)
+    }
+
+    fn into_result(self, ranges: &[HandRange], exact: bool) -> RangeEquityResult {
+        let combos = ranges
+            .iter()
+            .zip(&self.combos)
+            .map(|(range, tally)| {
+                range
+                    .combos()
+                    .filter_map(|(combo, weight)| {
+                        let &(share, boards) = tally.get(&combo_key(&combo))?;
+
+                        Some(ComboEquity {
+                            combo,
+                            weight,
+                            equity: share / boards * 100.,
+                            boards,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        RangeEquityResult {
+            equities: self.shares.iter().map(|share| share / self.weight).collect(),
+            combos,
+            boards: self.boards,
+            exact,
+            standard_error: if exact { 0. } else { self.sampling_error() },
+        }
+    }
+}
+
+/*
+    Equity of 2 to 10 players' weighted ranges. Combos conflicting with the board, dead cards or
+    each other are removed, so every deal is a valid one, weighted by the product of the combo
+    weights. With Method::Auto all combo assignments and boards are enumerated when there are at
+    most EXACT_BOARD_LIMIT of them, otherwise the given number of deals is sampled. Stratified
+    sampling is not supported for ranges.
+*/
+pub fn calculate_ranges(ranges: &[HandRange], board: &[Card], dead_cards: &[Card], method: Method) -> Result<RangeEquityResult, EquityError> {
+    if ranges.len() < MIN_PLAYERS || ranges.len() > MAX_PLAYERS {
+        return Err(EquityError::PlayerCount(ranges.len()));
+    }
+
+    if board.len() > 5 {
+        return Err(EquityError::BoardLength(board.len()));
+    }
+
+    let known: Vec<Card> = board.iter().chain(dead_cards).copied().collect();
+    check_duplicates(&known)?;
+
+    // Without duplicates at most the whole deck is known
+    let missing = 5 - board.len();
+    let (needed, left) = (2 * ranges.len() + missing, 52 - known.len());
+    if needed > left {
+        return Err(EquityError::NotEnoughCards { needed, left });
+    }
+
+    let ranges: Vec<HandRange> = ranges
+        .iter()
+        .map(|range| {
+            let mut range = range.clone();
+            range.remove_dead(&known);
+            range
+        })
+        .collect();
+
+    if let Some(player) = ranges.iter().position(HandRange::is_empty) {
+        return Err(EquityError::EmptyRange(player));
+    }
+
+    let boards_per_deal = binomial((left - 2 * ranges.len()) as u64, missing as u64);
+    let deals = ranges.iter().fold(1u64, |deals, range| deals.saturating_mul(range.len() as u64));
+
+    let exact = match method {
+        Method::Auto { .. } => deals.saturating_mul(boards_per_deal) <= EXACT_BOARD_LIMIT,
+        Method::Exact => true,
+        Method::MonteCarlo { .. } | Method::Adaptive { .. } => false,
+    };
+
+    let tally = match (exact, method) {
+        (true, _) => enumerate_ranges(&ranges, board, &known, missing),
+        (false, Method::Auto { iterations } | Method::MonteCarlo { iterations }) => {
+            sample_ranges(&ranges, board, &known, missing, iterations)
+        }
+        (false, Method::Adaptive { stratified: true, .. }) => return Err(EquityError::StratifiedRanges),
+        (false, Method::Adaptive { stop, max_iterations, .. }) => sample_ranges_adaptive(&ranges, board, &known, missing, stop, max_iterations),
+        (false, Method::Exact) => unreachable!(),
+    };
+
+    if tally.boards == 0 {
+        return Err(EquityError::ConflictingRanges);
+    }
+
+    Ok(tally.into_result(&ranges, exact))
+}
+
+fn enumerate_ranges(ranges: &[HandRange], board: &[Card], known: &[Card], missing: usize) -> RangeTally {
+    let mut deals = Vec::new();
+    collect_deals(ranges, &mut Vec::new(), 1., &mut deals);
+
+    deals
+        .into_par_iter()
+        .map(|(hole_cards, weight)| {
+            let mut tally = RangeTally::new(ranges.len());
+
+            let used: Vec<Card> = hole_cards.iter().flat_map(|&(first, second)| [first, second]).chain(known.iter().copied()).collect();
+            let remaining = remaining_cards(&used);
+            let states = hand_states(&hole_cards, board);
+
+            for_each_board(&states, &remaining, missing, &mut |states| {
+                tally.score(&hole_cards, &state_ranks(states)[..states.len()], weight)
+            });
+
+            tally
+        })
+        .reduce(|| RangeTally::new(ranges.len()), RangeTally::merge)
+}
+
+// All non-conflicting assignments of one combo per range, with their combined weight
+fn collect_deals(ranges: &[HandRange], prefix: &mut Vec<Combo>, weight: f64, deals: &mut Vec<(Vec<Combo>, f64)>) {
+    let Some(range) = ranges.get(prefix.len()) else {
+        deals.push((prefix.clone(), weight));
+        return;
+    };
+
+    for (combo, combo_weight) in range.combos() {
+        if prefix.iter().any(|other| conflicts(other, &combo)) {
+            continue;
+        }
+
+        prefix.push(combo);
+        collect_deals(ranges, prefix, weight * combo_weight as f64, deals);
+        prefix.pop();
+    }
+}
+
+// Deals with conflicting combos are rejected, which keeps the sampled deals correctly weighted
+fn sample_ranges(ranges: &[HandRange], board: &[Card], known: &[Card], missing: usize, iterations: u32) -> RangeTally {
+    let combos: Vec<Vec<Combo>> = ranges.iter().map(|range| range.combos().map(|(combo, _)| combo).collect()).collect();
+    let distributions: Vec<WeightedIndex<f32>> = ranges
+        .iter()
+        .map(|range| WeightedIndex::new(range.combos().map(|(_, weight)| weight)).unwrap())
+        .collect();
+
+    (0..iterations)
+        .into_par_iter()
+        .fold(
+            || (RangeTally::new(ranges.len()), Vec::with_capacity(ranges.len())),
+            |(mut tally, mut hole_cards), _| {
+                let mut rng = thread_rng();
+
+                hole_cards.clear();
+                for (combos, distribution) in combos.iter().zip(&distributions) {
+                    let combo = combos[distribution.sample(&mut rng)];
+
+                    if hole_cards.iter().any(|other| conflicts(other, &combo)) {
+                        return (tally, hole_cards);
+                    }
+
+                    hole_cards.push(combo);
+                }
+
+                let used: Vec<Card> = hole_cards.iter().flat_map(|&(first, second)| [first, second]).chain(known.iter().copied()).collect();
+                let mut remaining = remaining_cards(&used);
+                let (drawn, _) = remaining.partial_shuffle(&mut rng, missing);
+
+                let full_board: Vec<Card> = board.iter().chain(drawn.iter()).copied().collect();
+                tally.showdown(&hole_cards, &full_board, 1.);
+
+                (tally, hole_cards)
+            },
+        )
+        .map(|(tally, _)| tally)
+        .reduce(|| RangeTally::new(ranges.len()), RangeTally::merge)
+}
+
+// Deals rejected for conflicting combos count towards max_iterations, like the sampled ones
+fn sample_ranges_adaptive(
+    ranges: &[HandRange],
+    board: &[Card],
+    known: &[Card],
+    missing: usize,
+    stop: StopCondition,
+    max_iterations: u32,
+) -> RangeTally {
+    let start_time = Instant::now();
+    let mut tally = RangeTally::new(ranges.len());
+    let mut sampled = 0;
+
+    while sampled < max_iterations {
+        let batch = ADAPTIVE_BATCH_SIZE.min(max_iterations - sampled);
+
+        tally = tally.merge(sample_ranges(ranges, board, known, missing, batch));
+        sampled += batch;
+
+        if stop.is_met(tally.sampling_error(), start_time.elapsed()) {
+            break;
+        }
+    }
+
+    tally
+}
+
+fn combo_key(combo: &Combo) -> (usize, usize) {
+    let (first, second) = (combo.0.index(), combo.1.index());
+    (first.max(second), first.min(second))
+}
+
+fn conflicts(first: &Combo, second: &Combo) -> bool {
+    [first.0, first.1].iter().any(|card| card.is_same(&second.0) || card.is_same(&second.1))
+}
+
+fn check_duplicates(cards: &[Card]) -> Result<(), EquityError> {
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].iter().any(|other| other.is_same(card)) {
+            return Err(EquityError::DuplicateCard(*card));
+        }
+    }
+
+    Ok(())
+}
+
+// Indices of the players holding the best hand, and its ranking
+pub fn winners(hole_cards: &[Combo], board: &[Card]) -> (Vec<usize>, Ranking) {
+    let ranks = board_ranks(hole_cards, board);
+    let ranks = &ranks[..hole_cards.len()];
+    let best = *ranks.iter().max().unwrap();
+
+    ((0..ranks.len()).filter(|&i| ranks[i] == best).collect(), best.ranking())
+}
+
+// Hand ranks of up to MAX_PLAYERS players on a board, without allocating
+fn board_ranks(hole_cards: &[Combo], board: &[Card]) -> [HandRank; MAX_PLAYERS] {
+    let board = CardSet::from_cards(board);
+
+    let mut ranks = [HandRank::default(); MAX_PLAYERS];
+    for (rank, &(first, second)) in ranks.iter_mut().zip(hole_cards) {
+        *rank = evaluator::evaluate(board.with(first).with(second));
+    }
+
+    ranks
+}
+
+fn state_ranks(states: &[HandState]) -> [HandRank; MAX_PLAYERS] {
+    let mut ranks = [HandRank::default(); MAX_PLAYERS];
+    for (rank, state) in ranks.iter_mut().zip(states) {
+        *rank = state.rank();
+    }
+
+    ranks
+}
+
+pub fn hand_states(hole_cards: &[Combo], board: &[Card]) -> Vec<HandState> {
+    hole_cards
+        .iter()
+        .map(|&(first, second)| board.iter().copied().chain([first, second]).collect())
+        .collect()
+}
+
+pub fn remaining_cards(known: &[Card]) -> Vec<Card> {
+    (0..52)
+        .map(Card::from_index)
+        .filter(|card| !known.iter().any(|other| other.is_same(card)))
+        .collect()
+}
+
+pub fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+pub fn showdown_strength(hole_cards: Combo, board: &[Card]) -> Strength {
+    let hand = Hand::new(
+        hole_cards,
+        board.get(0..3).map(|flop| (flop[0], flop[1], flop[2])),
+        board.get(3).copied(),
+        board.get(4).copied(),
+    );
+
+    hand.strength()
+}
+
+// Parallelized over the first missing card, each worker enumerates the boards starting with it
+fn enumerate(hole_cards: &[Combo], board: &[Card], remaining: &[Card], missing: usize) -> EquityResult {
+    if missing == 0 {
+        let mut result = EquityResult::new(hole_cards.len(), true);
+        result.showdown(hole_cards, board, 1);
+        return result;
+    }
+
+    let states = hand_states(hole_cards, board);
+
+    (0..remaining.len())
+        .into_par_iter()
+        .map(|first| {
+            let mut result = EquityResult::new(hole_cards.len(), true);
+            let states: Vec<HandState> = states.iter().map(|state| state.with(remaining[first])).collect();
+
+            for_each_board(&states, &remaining[first + 1..], missing - 1, &mut |states| {
+                result.score(&state_ranks(states)[..states.len()], 1)
+            });
+
+            result
+        })
+        .reduce(|| EquityResult::new(hole_cards.len(), true), EquityResult::merge)
+}
+
+fn sample(hole_cards: &[Combo], board: &[Card], remaining: &[Card], missing: usize, iterations: u32) -> EquityResult {
+    (0..iterations)
+        .into_par_iter()
+        .fold(
+            || (EquityResult::new(hole_cards.len(), false), remaining.to_vec(), board.to_vec()),
+            |(mut result, mut cards, mut full_board), _| {
+                let (drawn, _) = cards.partial_shuffle(&mut thread_rng(), missing);
+
+                full_board.truncate(board.len());
+                full_board.extend_from_slice(drawn);
+                result.showdown(hole_cards, &full_board, 1);
+
+                (result, cards, full_board)
+            },
+        )
+        .map(|(result, _, _)| result)
+        .reduce(|| EquityResult::new(hole_cards.len(), false), EquityResult::merge)
+}
+
+// Sample in batches until the standard error of every player's equity is small enough
+fn sample_adaptive(
+    hole_cards: &[Combo],
+    board: &[Card],
+    remaining: &[Card],
+    missing: usize,
+    stop: StopCondition,
+    max_iterations: u32,
+) -> EquityResult {
+    let start_time = Instant::now();
+    let mut result = EquityResult::new(hole_cards.len(), false);
+
+    while result.boards < max_iterations as u64 {
+        let batch = ADAPTIVE_BATCH_SIZE.min(max_iterations - result.boards as u32);
+
+        result = result.merge(sample(hole_cards, board, remaining, missing, batch));
+        result.standard_error = result.sampling_error();
+
+        if stop.is_met(result.standard_error, start_time.elapsed()) {
+            break;
+        }
+    }
+
+    result
+}
+
+/*
+    Stratified sampling for preflop equity: flops are grouped into classes which are
+    suit-isomorphic given the hole cards. Every round samples one turn and river for one flop
+    of each class, weighted by the number of flops in it, which removes the variance between
+    flops. The standard error is estimated from the spread of the per-round equities.
+*/
+fn sample_stratified(hole_cards: &[Combo], remaining: &[Card], stop: StopCondition, max_iterations: u32) -> EquityResult {
+    let start_time = Instant::now();
+    let strata = flop_strata(hole_cards, remaining);
+
+    let mut result = EquityResult::new(hole_cards.len(), false);
+    let mut round_equities: Vec<Vec<f64>> = vec![Vec::new(); hole_cards.len()];
+
+    // At least two rounds are needed to estimate the standard error
+    let rounds = (max_iterations as usize / strata.len()).max(2);
+
+    for _ in 0..rounds {
+        let round = strata
+            .par_iter()
+            .map(|(flop, multiplicity)| {
+                let mut round = EquityResult::new(hole_cards.len(), false);
+
+                let mut cards: Vec<Card> = remaining.iter().filter(|card| !flop.iter().any(|other| other.is_same(card))).copied().collect();
+                let (drawn, _) = cards.partial_shuffle(&mut thread_rng(), 2);
+
+                round.showdown(hole_cards, &[flop[0], flop[1], flop[2], drawn[0], drawn[1]], *multiplicity as u64);
+                round
+            })
+            .reduce(|| EquityResult::new(hole_cards.len(), false), EquityResult::merge);
+
+        for (equities, player) in round_equities.iter_mut().zip(&round.players) {
+            equities.push(player.share / round.boards as f64);
+        }
+
+        result = result.merge(round);
+
+        result.standard_error = round_equities
+            .iter()
+            .map(|equities| {
+                let sum: f64 = equities.iter().sum();
+                let sum_of_squares: f64 = equities.iter().map(|equity| equity * equity).sum();
+
+                statistics::mean_standard_error(sum, sum_of_squares, equities.len() as f64)
+            })
+            .fold(0., f64::max);
+
+        if stop.is_met(result.standard_error, start_time.elapsed()) {
+            break;
+        }
+    }
+
+    result
+}
+
+// One flop per class of flops which are suit-isomorphic given the hole cards, and the class size
+fn flop_strata(hole_cards: &[Combo], remaining: &[Card]) -> Vec<([Card; 3], usize)> {
+    let hole_cards: Vec<[Card; 2]> = hole_cards.iter().map(|&(first, second)| [first, second]).collect();
+    let mut strata: HashMap<Vec<usize>, ([Card; 3], usize)> = HashMap::new();
+
+    for_each_combination(remaining, 3, &mut Vec::new(), &mut |flop| {
+        let mut groups: Vec<&[Card]> = hole_cards.iter().map(|cards| &cards[..]).collect();
+        groups.push(flop);
+
+        let key: Vec<usize> = canonical_suits(&groups).iter().flatten().map(Card::index).collect();
+        strata.entry(key).or_insert(([flop[0], flop[1], flop[2]], 0)).1 += 1;
+    });
+
+    let mut strata: Vec<([Card; 3], usize)> = strata.into_values().collect();
+    strata.sort_by_key(|(flop, _)| flop.map(|card| card.index()));
+
+    strata
+}
+
+/*
+    Call f with the states of all players extended by every way to deal k of the given cards
+    to the board. Each card is added once for all boards sharing it, so the states of a turn
+    are computed once for all of its rivers. At most MAX_PLAYERS states are supported.
+*/
+pub fn for_each_board(states: &[HandState], cards: &[Card], k: usize, f: &mut impl FnMut(&[HandState])) {
+    assert!(states.len() <= MAX_PLAYERS, "at most {MAX_PLAYERS} hands can share a board");
+
+    if k == 0 {
+        f(states);
+        return;
+    }
+
+    let mut next = [HandState::default(); MAX_PLAYERS];
+
+    for i in 0..cards.len() {
+        if cards.len() - i < k {
+            break;
+        }
+
+        for (next, state) in next.iter_mut().zip(states) {
+            *next = state.with(cards[i]);
+        }
+
+        for_each_board(&next[..states.len()], &cards[i + 1..], k - 1, f);
+    }
+}
+
+// Call f with every way to extend the prefix by k cards out of the given ones
+pub fn for_each_combination(cards: &[Card], k: usize, prefix: &mut Vec<Card>, f: &mut impl FnMut(&[Card])) {
+    if k == 0 {
+        f(prefix);
+        return;
+    }
+
+    for i in 0..cards.len() {
+        if cards.len() - i < k {
+            break;
+        }
+
+        prefix.push(cards[i]);
+        for_each_combination(&cards[i + 1..], k - 1, prefix, f);
+        prefix.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(text: &str) -> Vec<Card> {
+        text.split_whitespace().map(|card| card.parse().unwrap()).collect()
+    }
+
+    fn combo(text: &str) -> Combo {
+        let cards = cards(text);
+        (cards[0], cards[1])
+    }
+
+    #[test]
+    fn sampled_equity_is_close_to_the_exact_one() {
+        let (hole_cards, board) = ([combo("As Ks"), combo("Qh Qd"), combo("7c 7h")], cards("2s 7s Jc"));
+
+        let exact = calculate(&hole_cards, &board, &[], Method::Exact).unwrap();
+        assert!(exact.exact);
+        assert_eq!(exact.boards, binomial(43, 2));
+        assert_eq!(exact.standard_error, 0.);
+
+        let sampled = calculate(&hole_cards, &board, &[], Method::MonteCarlo { iterations: 200_000 }).unwrap();
+        assert!(!sampled.exact);
+        for player in 0..hole_cards.len() {
+            assert!((exact.equity_percentage(player) - sampled.equity_percentage(player)).abs() < 1., "player {player}");
+        }
+
+        let stop = StopCondition { target_standard_error: Some(0.002), time_budget: None };
+        let adaptive = calculate(&hole_cards, &board, &[], Method::Adaptive { stop, max_iterations: 1_000_000, stratified: false }).unwrap();
+        assert!(adaptive.standard_error <= 0.002);
+        for player in 0..hole_cards.len() {
+            assert!((exact.equity_percentage(player) - adaptive.equity_percentage(player)).abs() < 1., "player {player}");
+        }
+    }
+
+    #[test]
+    fn sampled_range_equity_is_close_to_the_exact_one() {
+        let ranges = [HandRange::parse("QQ+, AKs").unwrap(), HandRange::parse("JJ, TT, AQs+").unwrap()];
+        let board = cards("Ah 8d 3c");
+
+        let exact = calculate_ranges(&ranges, &board, &[], Method::Exact).unwrap();
+        assert!(exact.exact);
+        assert!((exact.equities.iter().sum::<f64>() - 1.).abs() < 1e-9);
+
+        let sampled = calculate_ranges(&ranges, &board, &[], Method::MonteCarlo { iterations: 200_000 }).unwrap();
+        assert!(!sampled.exact);
+        for player in 0..ranges.len() {
+            assert!((exact.equity_percentage(player) - sampled.equity_percentage(player)).abs() < 1., "player {player}");
+        }
+
+        let stop = StopCondition { target_standard_error: Some(0.002), time_budget: None };
+        let adaptive = calculate_ranges(&ranges, &board, &[], Method::Adaptive { stop, max_iterations: 1_000_000, stratified: false }).unwrap();
+        assert!(adaptive.standard_error <= 0.002);
+        for player in 0..ranges.len() {
+            assert!((exact.equity_percentage(player) - adaptive.equity_percentage(player)).abs() < 1., "player {player}");
+        }
+    }
+
+    #[test]
+    fn range_errors() {
+        let any = HandRange::parse("22+, A2+, K2+, Q2+, J2+, T2+, 92+, 82+, 72+, 62+, 52+, 42+, 32").unwrap();
+        let dead = remaining_cards(&[])[..30].to_vec();
+
+        // 10 hands and 5 board cards need 25 of the 22 cards left
+        let result = calculate_ranges(&vec![any.clone(); 10], &[], &dead, Method::Auto { iterations: 1000 });
+        assert_eq!(result.unwrap_err(), EquityError::NotEnoughCards { needed: 25, left: 22 });
+
+        // Too many deals of 10 ranges to enumerate, without overflowing when counting them
+        let result = calculate_ranges(&vec![any.clone(); 10], &[], &[], Method::Auto { iterations: 1000 }).unwrap();
+        assert!(!result.exact);
+
+        let stop = StopCondition { target_standard_error: Some(0.01), time_budget: None };
+        let result = calculate_ranges(&[any.clone(), any], &[], &[], Method::Adaptive { stop, max_iterations: 1000, stratified: true });
+        assert_eq!(result.unwrap_err(), EquityError::StratifiedRanges);
+    }
+}
@@ -0,0 +1,328 @@
+// https://en.wikipedia.org/wiki/Poker_probability#7-card_poker_hands
+// https://en.wikipedia.org/wiki/Bit_array
+
+use std::{fmt, iter::once};
+
+use rayon::prelude::*;
+
+use crate::{
+    card::{Card, Face},
+    game_logic::{Ranking, Strength},
+};
+
+const FACE_COUNT: usize = 13;
+const FACE_MASK: u16 = (1 << FACE_COUNT) - 1;
+
+// Up to 52 cards as bits of Card::index(), which is Copy and never allocates
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Hash)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut set = Self::new();
+        for &card in cards {
+            set.insert(card);
+        }
+        set
+    }
+
+    pub fn insert(&mut self, card: Card) {
+        self.0 |= 1 << card.index();
+    }
+
+    pub fn with(self, card: Card) -> Self {
+        Self(self.0 | 1 << card.index())
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & 1 << card.index() != 0
+    }
+
+    pub fn union(self, other: CardSet) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // 13-bit masks of the faces held in each suit (in the order of Card::index()) and the count of every face
+    fn faces(&self) -> ([u16; 4], [u8; FACE_COUNT]) {
+        let mut suits = [0; 4];
+        let mut counts = [0; FACE_COUNT];
+
+        let mut cards = self.0;
+        while cards != 0 {
+            let index = cards.trailing_zeros() as usize;
+            suits[index % 4] |= 1 << (index / 4);
+            counts[index / 4] += 1;
+            cards &= cards - 1;
+        }
+
+        (suits, counts)
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut set = Self::new();
+        for card in cards {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+/*
+    Evaluation state of the cards seen so far, which is extended one card at a time (the
+    flop, then the turn, then the river) instead of starting over. It is Copy, so the state
+    of a turn is computed once and shared by all of its rivers.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct HandState {
+    cards: CardSet,
+    suits: [u16; 4],
+    counts: [u8; FACE_COUNT],
+}
+
+impl HandState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut state = Self::new();
+        for &card in cards {
+            state.add(card);
+        }
+        state
+    }
+
+    // Adding a card which is already part of the state has no effect
+    pub fn add(&mut self, card: Card) {
+        if self.cards.contains(card) {
+            return;
+        }
+
+        let index = card.index();
+        self.cards.insert(card);
+        self.suits[index % 4] |= 1 << (index / 4);
+        self.counts[index / 4] += 1;
+    }
+
+    pub fn with(mut self, card: Card) -> Self {
+        self.add(card);
+        self
+    }
+
+    pub fn cards(&self) -> CardSet {
+        self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn rank(&self) -> HandRank {
+        rank(&self.suits, &self.counts)
+    }
+
+    // The rank as the Strength Hand::strength() computes, with cards of the state's suits
+    pub fn strength(&self) -> Strength {
+        let rank = self.rank();
+        let ranking = rank.ranking();
+
+        let flush_suit = (0..4).find(|&suit| self.suits[suit].count_ones() >= 5);
+        let card = |face: Face| {
+            let face_index = face as usize - 2;
+            let suit = match (ranking, flush_suit) {
+                (Ranking::Flush | Ranking::StraightFlush, Some(suit)) => suit,
+                _ => (0..4).rev().find(|&suit| self.suits[suit] & 1 << face_index != 0).unwrap(),
+            };
+            Card::from_index(face_index * 4 + suit)
+        };
+
+        let rank_card_count = match ranking {
+            Ranking::RoyalFlush => 0,
+            Ranking::TwoPair | Ranking::FullHouse => 2,
+            Ranking::Flush => 5,
+            _ => 1,
+        };
+
+        let cards: Vec<Card> = rank.faces().map(card).collect();
+        let (rank_cards, kicker_cards) = cards.split_at(rank_card_count.min(cards.len()));
+
+        Strength {
+            ranking,
+            rank_cards: (!rank_cards.is_empty()).then(|| rank_cards.to_vec()),
+            kicker_cards: (!kicker_cards.is_empty()).then(|| kicker_cards.to_vec()),
+        }
+    }
+}
+
+impl FromIterator<Card> for HandState {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut state = Self::new();
+        for card in cards {
+            state.add(card);
+        }
+        state
+    }
+}
+
+/*
+    A hand's strength packed into an integer: the ranking, followed by the faces of the rank
+    and kicker cards (4 bits each, in the order of Strength). Hand ranks compare exactly like
+    the Strength of the same cards computed by Hand::strength().
+*/
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Default, Hash)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    fn new(ranking: Ranking, faces: impl IntoIterator<Item = u8>) -> Self {
+        let faces = faces.into_iter().chain([0; 5]).take(5).fold(0, |rank, face| rank << 4 | face as u32);
+        Self((ranking as u32) << 20 | faces)
+    }
+
+    pub fn ranking(&self) -> Ranking {
+        Ranking::ALL[(self.0 >> 20) as usize]
+    }
+
+    // Faces of the rank cards followed by the kicker cards
+    pub fn faces(&self) -> impl Iterator<Item = Face> + '_ {
+        (0..5)
+            .map(|i| (self.0 >> (16 - i * 4) & 0xf) as u8)
+            .take_while(|&face| face != 0)
+            .map(|face| Face::ALL[Face::ALL.len() + 1 - face as usize])
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ranking())?;
+        for (i, face) in self.faces().enumerate() {
+            write!(f, "{}{}", if i == 0 { " " } else { "" }, face.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+/*
+    Evaluate up to 7 cards without allocating, using bit masks of the faces per suit. See
+    Hand::strength() for the rank and kicker cards of each ranking.
+*/
+pub fn evaluate(cards: CardSet) -> HandRank {
+    let (suits, counts) = cards.faces();
+    rank(&suits, &counts)
+}
+
+fn rank(suits: &[u16; 4], counts: &[u8; FACE_COUNT]) -> HandRank {
+    let faces = suits.iter().fold(0, |faces, suit| faces | suit);
+
+    if let Some(&flush) = suits.iter().find(|faces| faces.count_ones() >= 5) {
+        return match straight(flush) {
+            Some(14) => HandRank::new(Ranking::RoyalFlush, []),
+            Some(top) => HandRank::new(Ranking::StraightFlush, [top]),
+            None => HandRank::new(Ranking::Flush, top_faces(flush, 5)),
+        };
+    }
+
+    // Masks of the faces held 1, 2, 3 and 4 times
+    let mut held = [0u16; 5];
+    for (face, &count) in counts.iter().enumerate() {
+        held[count as usize] |= 1 << face;
+    }
+
+    if held[4] != 0 {
+        let quads = highest(held[4]);
+        return HandRank::new(Ranking::FourOfAKind, once(quads).chain(top_faces(without(faces, quads), 1)));
+    }
+
+    if held[3] != 0 {
+        let trips = highest(held[3]);
+        // A second set of trips counts as the pair
+        let pairs = without(held[3], trips) | held[2];
+
+        if pairs != 0 {
+            return HandRank::new(Ranking::FullHouse, [trips, highest(pairs)]);
+        }
+    }
+
+    if let Some(top) = straight(faces) {
+        return HandRank::new(Ranking::Straight, [top]);
+    }
+
+    if held[3] != 0 {
+        let trips = highest(held[3]);
+        return HandRank::new(Ranking::ThreeOfAKind, once(trips).chain(top_faces(without(faces, trips), 2)));
+    }
+
+    match held[2].count_ones() {
+        0 => HandRank::new(Ranking::HighCard, top_faces(faces, 5)),
+        1 => {
+            let pair = highest(held[2]);
+            HandRank::new(Ranking::OnePair, once(pair).chain(top_faces(without(faces, pair), 3)))
+        }
+        _ => {
+            // The kicker may also come from a third pair
+            let first = highest(held[2]);
+            let second = highest(without(held[2], first));
+            let kicker = top_faces(without(without(faces, first), second), 1);
+            HandRank::new(Ranking::TwoPair, [first, second].into_iter().chain(kicker))
+        }
+    }
+}
+
+/*
+    Evaluate many card sets at once, writing the rank of every set to the same position of
+    ranks. The work is split across rayon's thread pool.
+*/
+pub fn evaluate_batch(cards: &[CardSet], ranks: &mut [HandRank]) {
+    assert_eq!(cards.len(), ranks.len(), "every card set needs a rank to be written to");
+
+    cards
+        .par_iter()
+        .zip(ranks.par_iter_mut())
+        .with_min_len(1_024)
+        .for_each(|(&cards, rank)| *rank = evaluate(cards));
+}
+
+// Top face of the highest straight in a face mask, the wheel (5432A) counts as five high
+fn straight(faces: u16) -> Option<u8> {
+    let faces = (faces & FACE_MASK) << 1 | (faces >> 12 & 1);
+    let straights = faces & faces >> 1 & faces >> 2 & faces >> 3 & faces >> 4;
+
+    match straights {
+        0 => None,
+        straights => Some((15 - straights.leading_zeros()) as u8 + 5),
+    }
+}
+
+fn highest(faces: u16) -> u8 {
+    (15 - faces.leading_zeros()) as u8 + 2
+}
+
+fn without(faces: u16, face: u8) -> u16 {
+    faces & !(1 << (face - 2))
+}
+
+// Up to the given number of the highest faces in a face mask, highest first
+fn top_faces(mut faces: u16, count: usize) -> impl Iterator<Item = u8> {
+    (0..count).map_while(move |_| {
+        let face = (faces != 0).then(|| highest(faces))?;
+        faces = without(faces, face);
+        Some(face)
+    })
+}
@@ -111,7 +111,7 @@ impl Hand {
         self.eval_straight_flush(flush_result.as_ref())
             .or_else(|| self.eval_four_of_a_kind())
             .or_else(|| self.eval_full_house())
-            .or_else(|| flush_result)
+            .or(flush_result)
             .or_else(|| self.eval_straight(None))
             .or_else(|| self.eval_three_of_a_kind())
             .or_else(|| self.eval_two_pair())
@@ -230,7 +230,7 @@ impl Hand {
 
         // Test for special case 5432A straight ("wheel")
         if !is_straight && consecutive == 4 && top_card.unwrap().face == Face::Five
-            && self.cards[0].face == Face::Ace && match_suit.map(|suit| self.cards[0].suit == suit).unwrap_or(true) {
+            && self.cards.iter().any(|c| c.face == Face::Ace && match_suit.map(|suit| c.suit == suit).unwrap_or(true)) {
             is_straight = true;
         }
 
@@ -254,7 +254,8 @@ impl Hand {
         let second_pair_slot = self.face_count.get(1)?;
         
         if first_pair_slot.count >= 2 && second_pair_slot.count >= 2 {
-            let kicker_slot = self.face_count.get(2);
+            // Highest remaining card, which may also come from a third pair
+            let kicker_card = self.cards.iter().find(|c| c.face != first_pair_slot.face && c.face != second_pair_slot.face);
 
             Some(Strength {
                 ranking: Ranking::TwoPair,
@@ -262,7 +263,7 @@ impl Hand {
                     self.face_map.get(&first_pair_slot.face).unwrap()[0],     // first pair card
                     self.face_map.get(&second_pair_slot.face).unwrap()[0],    // second pair card
                 ]),
-                kicker_cards: kicker_slot.map(|kicker_slot| vec![self.face_map.get(&kicker_slot.face).unwrap()[0]]),
+                kicker_cards: kicker_card.map(|kicker_card| vec![*kicker_card]),
             })
         } else {
             None
@@ -294,10 +295,7 @@ impl Hand {
                         _ => unreachable!(),
                     },
                     rank_cards: Some(vec![*rank_card]),
-                    kicker_cards: match kicker_cards {
-                        Some(kicker_cards) => Some(kicker_cards.iter().take(kicker_count).cloned().collect()),
-                        None => None,
-                    },
+                    kicker_cards: kicker_cards.as_ref().map(|kicker_cards| kicker_cards.iter().take(kicker_count).cloned().collect()),
                 })
             } else {
                 None
@@ -314,7 +312,8 @@ struct FaceCount {
     pub count: usize,
 }
 
-#[derive(Debug)]
+// Strengths compare by ranking first, then rank card faces and finally kicker card faces
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
 pub struct Strength {
     pub ranking: Ranking,
     pub rank_cards: Option<Vec<Card>>,
@@ -336,6 +335,19 @@ pub enum Ranking {
 }
 
 impl Ranking {
+    pub const ALL: [Ranking; 10] = [
+        Ranking::HighCard,
+        Ranking::OnePair,
+        Ranking::TwoPair,
+        Ranking::ThreeOfAKind,
+        Ranking::Straight,
+        Ranking::Flush,
+        Ranking::FullHouse,
+        Ranking::FourOfAKind,
+        Ranking::StraightFlush,
+        Ranking::RoyalFlush,
+    ];
+
     #[rustfmt::skip]
     pub fn name(&self) -> &str {
         match self {
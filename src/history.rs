@@ -0,0 +1,228 @@
+// https://en.wikipedia.org/wiki/Hand_history
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    blinds::{Ante, Blinds},
+    card::Card,
+    game_logic::Strength,
+    range::Combo,
+    simulation::Street,
+    table::Action,
+};
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Post {
+    SmallBlind,
+    BigBlind,
+    Straddle,
+    Ante,
+    // Owed after sitting out, the big blind live and the small blind dead
+    MissedBigBlind,
+    MissedSmallBlind,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum EventKind {
+    Post { seat: usize, post: Post, amount: u32 },
+    Dealt { seat: usize, cards: Combo },
+    // Amount is the chips put in by the action, all-in if they were the last of the stack
+    Action { street: Street, seat: usize, action: Action, amount: u32, all_in: bool },
+    // The cards added to the board for the street
+    Board { street: Street, cards: Vec<Card> },
+    Show { seat: usize, cards: Combo, strength: Strength },
+    Uncalled { seat: usize, amount: u32 },
+    // Pot 0 is the main pot, followed by the side pots
+    Win { seat: usize, pot: usize, amount: u32 },
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Event {
+    pub time: SystemTime,
+    pub kind: EventKind,
+}
+
+// A player dealt into the hand, with the stack before posting
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SeatRecord {
+    pub seat: usize,
+    pub name: String,
+    pub stack: u32,
+}
+
+/*
+    Everything that happened in a hand as recorded by the table, in order: the posts, dealt
+    hole cards, actions, board cards, hands shown and chips awarded.
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct HandHistory {
+    // Hands are numbered per table, starting at 1
+    pub number: u64,
+    pub started: SystemTime,
+    pub structure: String,
+    pub blinds: Blinds,
+    pub seat_count: usize,
+    pub button: usize,
+    pub seats: Vec<SeatRecord>,
+    pub events: Vec<Event>,
+}
+
+impl HandHistory {
+    pub fn new(number: u64, structure: &str, blinds: Blinds, seat_count: usize, button: usize, seats: Vec<SeatRecord>) -> Self {
+        Self {
+            number,
+            started: SystemTime::now(),
+            structure: structure.to_string(),
+            blinds,
+            seat_count,
+            button,
+            seats,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, kind: EventKind) {
+        self.events.push(Event { time: SystemTime::now(), kind });
+    }
+
+    pub fn player(&self, seat: usize) -> Option<&SeatRecord> {
+        self.seats.iter().find(|record| record.seat == seat)
+    }
+
+    pub fn hole_cards(&self, seat: usize) -> Option<Combo> {
+        self.events.iter().find_map(|event| match event.kind {
+            EventKind::Dealt { seat: dealt, cards } if dealt == seat => Some(cards),
+            _ => None,
+        })
+    }
+
+    pub fn board(&self) -> Vec<Card> {
+        self.events
+            .iter()
+            .flat_map(|event| match &event.kind {
+                EventKind::Board { cards, .. } => cards.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    // Actions taken on the street, in order
+    pub fn actions(&self, street: Street) -> impl Iterator<Item = &EventKind> + '_ {
+        self.events.iter().map(|event| &event.kind).filter(move |kind| matches!(kind, EventKind::Action { street: action_street, .. } if *action_street == street))
+    }
+
+    // Chips received per seat over all pots and the uncalled bet
+    pub fn collected(&self, seat: usize) -> u32 {
+        self.events
+            .iter()
+            .map(|event| match event.kind {
+                EventKind::Uncalled { seat: winner, amount } | EventKind::Win { seat: winner, amount, .. } if winner == seat => amount,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // Chips put in by the seat with posts and actions, including any uncalled bet
+    pub fn invested(&self, seat: usize) -> u32 {
+        self.events
+            .iter()
+            .map(|event| match event.kind {
+                EventKind::Post { seat: poster, amount, .. } | EventKind::Action { seat: poster, amount, .. } if poster == seat => amount,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // Whether the hand is over, the chips being awarded or returned last
+    pub fn is_complete(&self) -> bool {
+        self.events.iter().any(|event| matches!(event.kind, EventKind::Win { .. } | EventKind::Uncalled { .. }))
+    }
+
+    /*
+        The blinds with the ante and straddle as posted in the hand, for hands read from formats
+        which only give the stakes. Antes posted by the big blind alone were a big blind ante.
+    */
+    pub fn posted_blinds(&self) -> Blinds {
+        let antes: Vec<(usize, u32)> = self
+            .events
+            .iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Post { seat, post: Post::Ante, amount } => Some((seat, amount)),
+                _ => None,
+            })
+            .collect();
+        let big_blind = self.events.iter().find_map(|event| match event.kind {
+            EventKind::Post { seat, post: Post::BigBlind, .. } => Some(seat),
+            _ => None,
+        });
+
+        Blinds {
+            ante: match antes[..] {
+                [] => Ante::None,
+                [(seat, amount)] if Some(seat) == big_blind => Ante::BigBlind(amount),
+                _ => Ante::Each(antes.iter().map(|&(_, amount)| amount).max().unwrap()),
+            },
+            straddle: self.events.iter().find_map(|event| match event.kind {
+                EventKind::Post { post: Post::Straddle, amount, .. } => Some(amount),
+                _ => None,
+            }),
+            ..self.blinds
+        }
+    }
+}
+
+// Calendar date and time in UTC to the second, for reading and writing hand history formats
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    pub fn new(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<Self> {
+        let date_time = Self { year, month, day, hour, minute, second };
+        let valid = (1..=12).contains(&month) && day >= 1 && hour < 24 && minute < 60 && second < 60;
+
+        // Out of range days roll over into the next month
+        (valid && Self::from_system_time(date_time.to_system_time()) == date_time).then_some(date_time)
+    }
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+        let (days, seconds) = (seconds.div_euclid(86_400) + 719_468, seconds.rem_euclid(86_400) as u32);
+
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+
+        Self {
+            year: year_of_era + era * 400 + (month <= 2) as i64,
+            month,
+            day: (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+        }
+    }
+
+    // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    pub fn to_system_time(&self) -> SystemTime {
+        let year = self.year - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let day_of_year = (153 * ((self.month as i64 + 9) % 12) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let seconds = days * 86_400 + (self.hour * 3600 + self.minute * 60 + self.second) as i64;
+        UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)
+    }
+}
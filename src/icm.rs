@@ -0,0 +1,278 @@
+// https://en.wikipedia.org/wiki/Independent_Chip_Model
+// https://en.wikipedia.org/wiki/Plackett%E2%80%93Luce_model
+
+use std::fmt;
+
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+
+// Compute the Malmuth-Harville model exactly for at most this many players with chips, otherwise sample
+pub const EXACT_PLAYER_LIMIT: usize = 16;
+// Method::Exact needs memory for every set of players with chips, so larger fields must be sampled
+pub const MAX_EXACT_PLAYERS: usize = 20;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Method {
+    Auto { iterations: u32 },
+    Exact,
+    // Samples finishing orders, for fields too large to compute exactly
+    MonteCarlo { iterations: u32 },
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum IcmError {
+    NoPlayers,
+    NoChips,
+    Payout(usize),
+    Player(usize),
+    Probability(f64),
+    TooManyPlayers(usize),
+}
+
+impl fmt::Display for IcmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcmError::NoPlayers => write!(f, "no stacks given"),
+            IcmError::NoChips => write!(f, "no player has chips"),
+            IcmError::Payout(place) => write!(f, "payout for place {} is not a non-negative amount", place + 1),
+            IcmError::Player(player) => write!(f, "player {player} does not exist or cannot be both shover and caller"),
+            IcmError::Probability(probability) => write!(f, "probability {probability} is outside of 0 to 1"),
+            IcmError::TooManyPlayers(players) => write!(f, "{players} players with chips are more than {MAX_EXACT_PLAYERS} computed exactly"),
+        }
+    }
+}
+
+impl std::error::Error for IcmError {}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct IcmResult {
+    // Expected prize money of each player, in the order of the stacks
+    pub equities: Vec<f64>,
+    pub exact: bool,
+}
+
+/*
+    Prize money equity of every player given their chip stacks and the payouts by place
+    (first place first). A player finishes first with the share of all chips they hold,
+    and the remaining places follow recursively among the other players. Players without
+    chips share the places below everyone who has chips. With Method::Auto the model is
+    computed exactly for at most EXACT_PLAYER_LIMIT players with chips, otherwise sampled.
+    Method::Exact accepts at most MAX_EXACT_PLAYERS players with chips.
+*/
+pub fn equities(stacks: &[u32], payouts: &[f64], method: Method) -> Result<IcmResult, IcmError> {
+    if stacks.is_empty() {
+        return Err(IcmError::NoPlayers);
+    }
+
+    if let Some(place) = payouts.iter().position(|payout| !payout.is_finite() || *payout < 0.) {
+        return Err(IcmError::Payout(place));
+    }
+
+    let alive: Vec<usize> = (0..stacks.len()).filter(|&player| stacks[player] > 0).collect();
+    if alive.is_empty() {
+        return Err(IcmError::NoChips);
+    }
+
+    if method == Method::Exact && alive.len() > MAX_EXACT_PLAYERS {
+        return Err(IcmError::TooManyPlayers(alive.len()));
+    }
+
+    let live_stacks: Vec<f64> = alive.iter().map(|&player| stacks[player] as f64).collect();
+    let paid = &payouts[..payouts.len().min(alive.len())];
+
+    let exact = match method {
+        Method::Auto { .. } => alive.len() <= EXACT_PLAYER_LIMIT,
+        Method::Exact => true,
+        Method::MonteCarlo { .. } => false,
+    };
+
+    let live_equities = match (exact, method) {
+        (true, _) => harville(&live_stacks, paid),
+        (false, Method::Auto { iterations } | Method::MonteCarlo { iterations }) => sample(&live_stacks, paid, iterations),
+        (false, Method::Exact) => unreachable!(),
+    };
+
+    let busted = stacks.len() - alive.len();
+    let busted_places = payouts.iter().skip(alive.len()).take(busted);
+    let busted_equity = busted_places.sum::<f64>() / busted.max(1) as f64;
+
+    let mut equities = vec![busted_equity; stacks.len()];
+    for (&player, equity) in alive.iter().zip(live_equities) {
+        equities[player] = equity;
+    }
+
+    Ok(IcmResult { equities, exact })
+}
+
+/*
+    Iterates over the sets of players who took the places above the next one. The
+    probability of a set does not depend on the order in which its players finished, so
+    every subset is visited once, after all of its own subsets, which keeps 10 players instantaneous.
+*/
+fn harville(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    let total: f64 = stacks.iter().sum();
+    let mut equities = vec![0.; stacks.len()];
+
+    let mut probabilities = vec![0.; 1 << stacks.len()];
+    let mut remaining = vec![total; 1 << stacks.len()];
+    probabilities[0] = 1.;
+
+    for set in 0..probabilities.len() {
+        let finished = (set as u32).count_ones() as usize;
+        let probability = probabilities[set];
+
+        if finished >= payouts.len() || probability == 0. {
+            continue;
+        }
+
+        for (player, &stack) in stacks.iter().enumerate() {
+            if set & (1 << player) != 0 {
+                continue;
+            }
+
+            let next = probability * stack / remaining[set];
+            equities[player] += next * payouts[finished];
+
+            probabilities[set | 1 << player] += next;
+            remaining[set | 1 << player] = remaining[set] - stack;
+        }
+    }
+
+    equities
+}
+
+/*
+    Finishing orders are drawn by giving every player an exponentially distributed time
+    inversely proportional to their stack: the fastest finishes first, and because the
+    exponential distribution is memoryless the rest again follow the model.
+*/
+fn sample(stacks: &[f64], payouts: &[f64], iterations: u32) -> Vec<f64> {
+    let totals = (0..iterations)
+        .into_par_iter()
+        .fold(
+            || (vec![0.; stacks.len()], Vec::with_capacity(stacks.len())),
+            |(mut totals, mut times): (Vec<f64>, Vec<(f64, usize)>), _| {
+                let mut rng = thread_rng();
+
+                times.clear();
+                times.extend(stacks.iter().enumerate().map(|(player, stack)| {
+                    let uniform: f64 = rng.gen();
+                    (-(1. - uniform).ln() / stack, player)
+                }));
+
+                let places = payouts.len().min(times.len());
+                if places < times.len() {
+                    times.select_nth_unstable_by(places, |a, b| a.0.total_cmp(&b.0));
+                }
+                times[..places].sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+                for (&(_, player), payout) in times.iter().zip(payouts) {
+                    totals[player] += payout;
+                }
+
+                (totals, times)
+            },
+        )
+        .map(|(totals, _)| totals)
+        .reduce(
+            || vec![0.; stacks.len()],
+            |mut totals, other| {
+                for (total, other) in totals.iter_mut().zip(other) {
+                    *total += other;
+                }
+                totals
+            },
+        );
+
+    totals.into_iter().map(|total| total / iterations.max(1) as f64).collect()
+}
+
+// A player moved all-in and another one decides whether to call, everyone else has folded
+#[derive(Clone, Debug)]
+pub struct AllIn<'a> {
+    // Chips of every player before the hand
+    pub stacks: &'a [u32],
+    // Chips every player has already put into the pot (blinds and antes)
+    pub committed: &'a [u32],
+    pub shover: usize,
+    pub caller: usize,
+}
+
+// Prize money equity of the caller after each outcome of the decision
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct CallDecision {
+    pub fold: f64,
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+    pub call: f64,
+}
+
+impl CallDecision {
+    pub fn should_call(&self) -> bool {
+        self.call > self.fold
+    }
+
+    // Probability of winning the showdown (ignoring ties) at which calling and folding are worth the same
+    pub fn break_even_equity(&self) -> f64 {
+        match self.win - self.lose {
+            difference if difference > 0. => ((self.fold - self.lose) / difference).clamp(0., 1.),
+            _ => 1.,
+        }
+    }
+}
+
+/*
+    $EV of calling and folding for the caller, given the probabilities of winning and
+    tying the showdown (e.g. from equity::calculate, as fractions). The stacks after each
+    outcome are valued with the ICM.
+*/
+pub fn call_or_fold(all_in: &AllIn, win: f64, tie: f64, payouts: &[f64], method: Method) -> Result<CallDecision, IcmError> {
+    let players = all_in.stacks.len();
+    for player in [all_in.shover, all_in.caller] {
+        if player >= players || all_in.shover == all_in.caller {
+            return Err(IcmError::Player(player));
+        }
+    }
+
+    for probability in [win, tie, win + tie] {
+        if !(0. ..=1.).contains(&probability) {
+            return Err(IcmError::Probability(probability));
+        }
+    }
+
+    let committed = |player: usize| all_in.committed.get(player).copied().unwrap_or(0).min(all_in.stacks[player]);
+    let pot: u32 = (0..players).map(committed).sum();
+    let behind: Vec<u32> = (0..players).map(|player| all_in.stacks[player] - committed(player)).collect();
+
+    // Every player keeps what is behind, the pot goes to the winner
+    let (shover, caller) = (all_in.shover, all_in.caller);
+    // Both put in what the smaller stack can match in total, any blind above it is returned
+    let matched = all_in.stacks[shover].min(all_in.stacks[caller]);
+    let outcome = |shover_share: u32, caller_share: u32, called: bool| {
+        let mut stacks = behind.clone();
+        if called {
+            stacks[shover] = all_in.stacks[shover] - matched;
+            stacks[caller] = all_in.stacks[caller] - matched;
+        }
+        stacks[shover] += shover_share;
+        stacks[caller] += caller_share;
+        equities(&stacks, payouts, method).map(|result| result.equities[caller])
+    };
+
+    let fold = outcome(pot, 0, false)?;
+
+    let called_pot = pot - committed(shover) - committed(caller) + 2 * matched;
+    let win_ev = outcome(0, called_pot, true)?;
+    let lose_ev = outcome(called_pot, 0, true)?;
+    // An odd chip of a split pot is counted for the caller
+    let tie_ev = outcome(called_pot / 2, called_pot - called_pot / 2, true)?;
+
+    Ok(CallDecision {
+        fold,
+        win: win_ev,
+        tie: tie_ev,
+        lose: lose_ev,
+        call: win * win_ev + tie * tie_ev + (1. - win - tie) * lose_ev,
+    })
+}
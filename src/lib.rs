@@ -0,0 +1,24 @@
+pub mod betting;
+pub mod blinds;
+pub mod canonical;
+pub mod cash_game;
+pub mod card;
+pub mod deck;
+pub mod equity;
+pub mod evaluator;
+pub mod game_logic;
+pub mod history;
+pub mod icm;
+pub mod ohh;
+pub mod outlook;
+pub mod phh;
+pub mod pokerstars;
+pub mod pot;
+pub mod preflop;
+pub mod range;
+pub mod render;
+pub mod replay;
+pub mod simulation;
+pub mod statistics;
+pub mod table;
+pub mod tournament;
@@ -1,15 +1,13 @@
-use std::{collections::HashMap, io::{self, Write}, sync::{Arc, Mutex}, time::Instant};
+use std::io::{self, Write};
 
-use rayon::prelude::*;
+use rand::Rng;
 
-use crate::{
-    card::{Card, Face, Suit}, deck::Deck, game_logic::{Hand, Ranking}
+use rust_texas_holdem::{
+    blinds::{Ante, Blinds}, card::{Card, Face, Suit}, cash_game::{CashGame, CashGameConfig}, deck::Deck, equity::{self, Method}, game_logic::Ranking, icm, ohh, outlook, phh, pokerstars, render::{Charset, ColorScheme, Renderer},
+    simulation::{self, SimulationConfig}, table::{Action, Player, Table},
+    tournament::{self, LevelLength, Schedule, Tournament, TournamentConfig},
 };
 
-pub mod card;
-pub mod deck;
-pub mod game_logic;
-
 fn main() {
     println!("Rust Texas Holdem");
 
@@ -65,6 +63,47 @@ fn main() {
     let top_card = deck.pop().unwrap();
     println!("Top card: {}", top_card);
 
+    println!("----------------------------");
+
+    let renderer = Renderer::new(ColorScheme::FourColor, Charset::Unicode);
+
+    let hole_cards = [deck.pop().unwrap(), deck.pop().unwrap()];
+    println!("{}", renderer.hand(&hole_cards));
+
+    let board = [deck.pop().unwrap(), deck.pop().unwrap(), deck.pop().unwrap()];
+    println!("{}", renderer.board(&board));
+
+    println!("----------------------------");
+
+    let players = [
+        (Card { face: Face::Ace, suit: Suit::Hearts }, Card { face: Face::King, suit: Suit::Hearts }),
+        (Card { face: Face::Two, suit: Suit::Clubs }, Card { face: Face::Two, suit: Suit::Diamonds }),
+    ];
+    let flop = [
+        Card { face: Face::Three, suit: Suit::Hearts },
+        Card { face: Face::Seven, suit: Suit::Hearts },
+        Card { face: Face::Jack, suit: Suit::Diamonds },
+    ];
+
+    let result = equity::calculate(&players, &flop, &[], Method::Auto { iterations: 100_000 }).unwrap();
+
+    println!("Equity on {} {} {} ({} boards)", flop[0], flop[1], flop[2], result.boards);
+    for (i, (first, second)) in players.iter().enumerate() {
+        println!("{first}{second}: win {:>7.3} %  tie {:>7.3} %  equity {:>7.3} %",
+            result.win_percentage(i), result.tie_percentage(i), result.equity_percentage(i));
+    }
+
+    println!("----------------------------");
+
+    for distribution in outlook::evolution(players[0], &flop).unwrap() {
+        println!("{:?} ({} boards)", distribution.street, distribution.boards);
+        for ranking in Ranking::ALL.into_iter().filter(|&ranking| distribution.count(ranking) > 0) {
+            println!("{:>15}: {:>7.3} %", ranking.name(), distribution.probability(ranking) * 100.);
+        }
+    }
+
+    println!("----------------------------");
+
     for ranking in [
         Ranking::HighCard,
         Ranking::OnePair,
@@ -179,68 +218,224 @@ fn main() {
 
     // println!("{:?}\n{:?}", hand, hand.strength());
 
+    let stacks = [5_000, 3_000, 2_000, 500];
+    let payouts = [50., 30., 20.];
+    let result = icm::equities(&stacks, &payouts, icm::Method::Exact).unwrap();
+
+    println!("ICM with payouts {payouts:?}");
+    for (stack, equity) in stacks.iter().zip(&result.equities) {
+        println!("{stack:>6} chips: $ {equity:>6.2}");
+    }
+
+    // The short stack shoves 2c2d from the small blind, the big blind decides with AhKh
+    let showdown = equity::calculate(&[players[0], players[1]], &[], &[], Method::MonteCarlo { iterations: 100_000 }).unwrap();
+    let all_in = icm::AllIn { stacks: &stacks, committed: &[0, 0, 200, 100], shover: 3, caller: 2 };
+    let decision = icm::call_or_fold(
+        &all_in,
+        showdown.win_percentage(0) / 100.,
+        showdown.tie_percentage(0) / 100.,
+        &payouts,
+        icm::Method::Exact,
+    ).unwrap();
+
+    println!("{}{} calls {}{}: $ {:.2}, fold: $ {:.2}, break even at {:.1} % ({})",
+        players[0].0, players[0].1, players[1].0, players[1].1,
+        decision.call, decision.fold, decision.break_even_equity() * 100.,
+        if decision.should_call() { "call" } else { "fold" });
+
+    println!("----------------------------");
+
+    play_hand(&renderer);
+
+    println!("----------------------------");
+
+    sit_and_go();
+
+    println!("----------------------------");
+
+    cash_game();
+
+    println!("----------------------------");
+
     simulate(1_000_000);
+
+    println!("----------------------------");
+
+    showdowns(6, 1_000_000);
 }
 
 fn simulate(iterations: u32) {
     println!("Simulating {iterations} hands.");
 
-    let keys = vec![
-        Ranking::RoyalFlush,
-        Ranking::StraightFlush,
-        Ranking::FourOfAKind,
-        Ranking::Flush,
-        Ranking::Straight,
-        Ranking::ThreeOfAKind,
-        Ranking::TwoPair,
-        Ranking::OnePair,
-        Ranking::HighCard,
-    ];
-    let results: Arc<Mutex<HashMap<Ranking, usize>>> = Arc::new(Mutex::new(keys.into_iter().map(|key| (key, 0)).collect()));
+    let config = SimulationConfig { iterations, ..Default::default() };
+
+    let result = simulation::simulate(&config, |_, _| {
+        print!(".");
+        io::stdout().flush().unwrap_or_default();
+    }).unwrap();
+
+    println!();
+
+    let validation = result.validate(0.99, 0.001);
+
+    for check in validation.rankings.iter().rev() {
+        println!("{:>15}: {:>10.6} %  {:>10.6} %  (Δ {:>+10.6} %)  99 % CI [{:>10.6} %, {:>10.6} %]{}",
+            check.ranking.name(),
+            check.observed * 100.,
+            check.expected * 100.,
+            (check.observed - check.expected) * 100.,
+            check.interval.lower * 100.,
+            check.interval.upper * 100.,
+            if check.is_within() { "" } else { "  *" });
+    }
+
+    println!();
+    println!("Chi-square: {:.3} ({} degrees of freedom), p = {:.4}: {}",
+        validation.chi_square.statistic,
+        validation.chi_square.degrees_of_freedom,
+        validation.chi_square.p_value,
+        if validation.passed() { "passed" } else { "FAILED, evaluator or shuffle is biased" });
+
+    println!();
+    println!("Simulation time: {:9.3} seconds ({:.3} us per iteration, {:.0} hands per second)",
+        result.elapsed.as_secs_f32(),
+        result.micros_per_hand(),
+        result.hands_per_second());
+    println!("Throughput target: {:.0} hands per second on {} threads ({})",
+        result.target_hands_per_second(),
+        result.threads,
+        if result.meets_throughput_target() { "met" } else { "missed" });
+}
+
+fn showdowns(players: usize, iterations: u32) {
+    println!("Simulating {iterations} showdowns at {players} player tables.");
+
+    let config = SimulationConfig { iterations, players, ..Default::default() };
+    let result = simulation::simulate_showdowns(&config, |_, _| {}).unwrap();
+
+    for (hand, stats) in result.ranked().into_iter().take(10) {
+        println!("{:>4}: win {:>7.3} %  tie {:>7.3} %  lose {:>7.3} %",
+            hand.to_string(),
+            stats.win_rate() * 100.,
+            stats.tie_rate() * 100.,
+            stats.loss_rate() * 100.);
+    }
+}
 
-    let start_time = Instant::now();
+// Play one hand where everyone checks or calls, then show the table
+fn play_hand(renderer: &Renderer) {
+    let mut table = Table::new(4, Blinds { ante: Ante::BigBlind(10), ..Blinds::new(5, 10) }).unwrap();
+    for (seat, name) in ["Alice", "Bob", "Carol", "Dave"].into_iter().enumerate() {
+        table.sit(seat, Player::new(name), 1_000).unwrap();
+    }
+
+    table.start_hand(&mut rand::thread_rng()).unwrap();
+
+    while let Some(legal) = table.legal_actions() {
+        let action = if legal.check { Action::Check } else { Action::Call };
+        println!("{:?} {}: {action}", table.phase(), table.seat(legal.seat).unwrap().player.name);
+        table.act(action).unwrap();
+    }
+
+    println!("{}", renderer.table(&table.view(true)));
 
-    (0..iterations).into_par_iter().for_each(|i| {
-        let mut deck = Deck::new();
-        
-        deck.shuffle();
+    let result = table.result().unwrap();
+    for (seat, strength) in &result.shown {
+        println!("{} shows {}", table.seat(*seat).unwrap().player.name, strength.ranking);
+    }
+    print!("{}", result.pots);
 
-        let hand = Hand::new(
-            (deck.pop().unwrap(), deck.pop().unwrap()),
-            Some((deck.pop().unwrap(), deck.pop().unwrap(), deck.pop().unwrap())),
-            deck.pop(),
-            deck.pop());
+    let history = table.history().unwrap();
+    let duration = history.events.last().unwrap().time.duration_since(history.started).unwrap_or_default();
+    println!("Hand #{} recorded {} events in {duration:?}", history.number, history.events.len());
 
-        let strength = hand.strength();
+    let text = pokerstars::write(history);
+    print!("{text}");
+    let parsed = pokerstars::parse(&text).unwrap();
+    println!("Parsed back hand #{} with {} events", parsed[0].number, parsed[0].events.len());
 
-        let mut locked_results = results.lock().unwrap();
-        let result = locked_results.entry(strength.ranking).or_insert(0);
-        *result += 1;
+    let json = ohh::write(history);
+    let parsed = ohh::parse(&json).unwrap();
+    println!("Open Hand History export of {} bytes parsed back with {} events", json.len(), parsed[0].events.len());
 
-        if i % 10_000 == 0 {
-            print!(".");
-            io::stdout().flush().unwrap_or_default();
+    let text = phh::write(history).unwrap();
+    let parsed = phh::parse(&text).unwrap();
+    println!("Poker Hand History export of {} lines parsed back with {} events", text.lines().count(), parsed[0].events.len());
+}
+
+// Play a six player sit & go where players shove, call or fold at random
+fn sit_and_go() {
+    let config = TournamentConfig {
+        starting_stack: 1_500,
+        schedule: Schedule::sit_and_go(LevelLength::Hands(10)),
+        prize_pool: 6 * 10,
+        payouts: tournament::standard_payouts(6),
+    };
+    let mut tournament = Tournament::new(config, &["Alice", "Bob", "Carol", "Dave", "Erin", "Frank"]).unwrap();
+    let mut rng = rand::thread_rng();
+
+    while !tournament.is_finished() {
+        tournament.start_hand(&mut rng).unwrap();
+
+        while let Some(legal) = tournament.table().legal_actions() {
+            let action = match (legal.raise.or(legal.bet), rng.gen_range(0..10)) {
+                (Some((_, all_in)), 0) if legal.raise.is_some() => Action::Raise(all_in),
+                (Some((_, all_in)), 0) => Action::Bet(all_in),
+                _ if legal.check => Action::Check,
+                (_, 1..=5) => Action::Call,
+                _ => Action::Fold,
+            };
+            tournament.act(action).unwrap();
         }
-    });
+    }
 
-    println!();
+    println!("Sit & go finished after {} hands at level {} ({:?})", tournament.hands_played(), tournament.level() + 1, tournament.blinds());
+    for standing in tournament.standings() {
+        println!("{}. {:<6} $ {}", standing.place, standing.name, standing.prize.unwrap_or(0));
+    }
+}
+
+// Play a few cash game hands where players come and go, then show the ledger
+fn cash_game() {
+    let mut game = CashGame::new(CashGameConfig { seats: 6, blinds: Blinds::new(1, 2), min_buy_in: 40, max_buy_in: 200 }).unwrap();
+    let mut rng = rand::thread_rng();
+
+    for (seat, name) in ["Alice", "Bob", "Carol"].into_iter().enumerate() {
+        game.join(seat, name, 100).unwrap();
+    }
 
-    let results = Arc::try_unwrap(results).unwrap().into_inner().unwrap();
+    for hand in 0..20 {
+        match hand {
+            5 => game.join(4, "Dave", 200).unwrap(),
+            10 => game.sit_out(1).unwrap(),
+            15 => game.sit_in(1).unwrap(),
+            _ => {}
+        }
 
-    let mut sorted_results: Vec<_> = results.into_iter().collect();
-    sorted_results.sort_by_key(|entry| entry.0);
-    sorted_results.reverse();
+        // Top up or rebuy to the maximum whoever is short
+        let short: Vec<(usize, u32)> = game.table().seats().filter(|(_, seat)| seat.stack < 100).map(|(i, seat)| (i, seat.stack)).collect();
+        for (seat, stack) in short {
+            game.add_chips(seat, 200 - stack).unwrap();
+        }
 
-    for (ranking, count) in sorted_results {
-        let probability = ranking.probability();
-        let impiric_probability = count as f32 / iterations as f32;
-        println!("{:>15}: {:>10.6} %  {:>10.6} %  (Δ {:>+10.6} %)", ranking.name(), impiric_probability * 100., probability * 100., (impiric_probability - probability) * 100.);
+        game.start_hand(&mut rng).unwrap();
+        while let Some(legal) = game.table().legal_actions() {
+            let action = match (legal.raise.or(legal.bet), rng.gen_range(0..4)) {
+                (Some((min, _)), 0) if legal.raise.is_some() => Action::Raise(min),
+                (Some((min, _)), 0) => Action::Bet(min),
+                _ if legal.check => Action::Check,
+                (_, 1..=2) => Action::Call,
+                _ => Action::Fold,
+            };
+            game.act(action).unwrap();
+        }
     }
 
-    let elapsed_time = start_time.elapsed();
+    game.leave(0).unwrap();
 
-    println!();
-    println!("Simulation time: {:9.3} seconds ({:.3} us per iteration)",
-        elapsed_time.as_secs_f32(),
-        (elapsed_time.as_secs_f32() / iterations as f32) * 1_000_000f32);
+    println!("Cash game ledger after {} hands", game.hands_played());
+    for player in game.ledger().players() {
+        let balance = game.ledger().balance(player);
+        println!("{player:<6} bought in {:>4}, won {:>5}, cashed out {:>4}", balance.bought_in, balance.won, balance.cashed_out);
+    }
 }
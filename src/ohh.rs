@@ -0,0 +1,588 @@
+// https://hh-specs.handhistory.org/
+// https://en.wikipedia.org/wiki/Hand_history
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    betting::{BettingStructure, FixedLimit, NoLimit, PotLimit},
+    blinds::{Ante, Blinds},
+    card::Card,
+    history::{DateTime, EventKind, HandHistory, Post, SeatRecord},
+    replay::{self, Replay, ReplayError},
+    simulation::Street,
+    table::{Action, MAX_SEATS},
+};
+
+// Version of the Open Hand History specification written
+pub const SPEC_VERSION: &str = "1.4.7";
+
+// Name written for the site, network and table, which the hand histories do not record
+pub const SITE_NAME: &str = "rust-texas-holdem";
+
+// Currency written for hands played in chips rather than money
+pub const CURRENCY: &str = "CHIPS";
+
+#[derive(Debug)]
+pub enum OhhError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Unsupported(String),
+    Invalid(String),
+    UnknownPlayer(u64),
+    Replay(ReplayError),
+    // An action which could not have been taken, by action number
+    Action { number: u32, error: ReplayError },
+    // Chips awarded and raked do not add up to the chips put in the pots
+    Pots { awarded: u32, expected: u32 },
+}
+
+impl fmt::Display for OhhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OhhError::Io(error) => write!(f, "{error}"),
+            OhhError::Json(error) => write!(f, "{error}"),
+            OhhError::Unsupported(message) => write!(f, "unsupported {message}"),
+            OhhError::Invalid(message) => write!(f, "invalid {message}"),
+            OhhError::UnknownPlayer(id) => write!(f, "unknown player id {id}"),
+            OhhError::Replay(error) => write!(f, "{error}"),
+            OhhError::Action { number, error } => write!(f, "action {number}: {error}"),
+            OhhError::Pots { awarded, expected } => write!(f, "pots award {awarded}, expected {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for OhhError {}
+
+impl From<io::Error> for OhhError {
+    fn from(error: io::Error) -> Self {
+        OhhError::Io(error)
+    }
+}
+
+impl From<ReplayError> for OhhError {
+    fn from(error: ReplayError) -> Self {
+        OhhError::Replay(error)
+    }
+}
+
+impl From<serde_json::Error> for OhhError {
+    fn from(error: serde_json::Error) -> Self {
+        OhhError::Json(error)
+    }
+}
+
+// The JSON objects of the specification, with only the fields used here
+#[derive(Serialize, Deserialize)]
+struct OhhFile {
+    ohh: Ohh,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Ohh {
+    spec_version: String,
+    site_name: String,
+    network_name: String,
+    internal_version: String,
+    tournament: bool,
+    game_number: String,
+    start_date_utc: String,
+    table_name: String,
+    game_type: String,
+    bet_limit: BetLimit,
+    table_size: usize,
+    currency: String,
+    dealer_seat: usize,
+    small_blind_amount: f64,
+    big_blind_amount: f64,
+    ante_amount: f64,
+    flags: Vec<String>,
+    players: Vec<OhhPlayer>,
+    rounds: Vec<Round>,
+    pots: Vec<OhhPot>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct BetLimit {
+    bet_type: String,
+    bet_cap: f64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OhhPlayer {
+    id: u64,
+    seat: usize,
+    name: String,
+    starting_stack: f64,
+    is_sitting_out: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Round {
+    id: u32,
+    street: String,
+    cards: Vec<String>,
+    actions: Vec<OhhAction>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OhhAction {
+    action_number: u32,
+    player_id: u64,
+    action: String,
+    amount: f64,
+    is_allin: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cards: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OhhPot {
+    number: usize,
+    amount: f64,
+    rake: f64,
+    jackpot: f64,
+    player_wins: Vec<PlayerWin>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct PlayerWin {
+    player_id: u64,
+    win_amount: f64,
+    contributed_rake: f64,
+}
+
+/*
+    Write a hand in the Open Hand History JSON format. Players are identified by their seat
+    index and seated from 1, amounts are in chips. An action amount is the chips put in
+    with it, so a raise from 10 to 30 is written as 20. The uncalled bet, which the format
+    does not record, follows from the actions.
+*/
+pub fn write(history: &HandHistory) -> String {
+    let started = DateTime::from_system_time(history.started);
+    let id = |seat: usize| seat as u64;
+
+    let mut rounds = vec![Round { id: 0, street: "Preflop".to_string(), ..Round::default() }];
+    let mut pots: Vec<OhhPot> = Vec::new();
+
+    for event in &history.events {
+        let action = |player: usize, action: &str, amount: u32, is_allin: bool, cards: Vec<Card>| OhhAction {
+            action_number: 0,
+            player_id: id(player),
+            action: action.to_string(),
+            amount: amount as f64,
+            is_allin,
+            cards: cards.iter().map(Card::to_string).collect(),
+        };
+
+        let action = match &event.kind {
+            EventKind::Post { seat, post, amount } => {
+                let name = match post {
+                    Post::SmallBlind => "Post SB",
+                    Post::BigBlind => "Post BB",
+                    Post::Straddle => "Straddle",
+                    Post::Ante => "Post Ante",
+                    Post::MissedBigBlind => "Post Extra Blind",
+                    Post::MissedSmallBlind => "Post Dead",
+                };
+                action(*seat, name, *amount, false, Vec::new())
+            }
+            EventKind::Dealt { seat, cards } => action(*seat, "Dealt Cards", 0, false, vec![cards.0, cards.1]),
+            EventKind::Action { seat, action: taken, amount, all_in, .. } => {
+                let name = match taken {
+                    Action::Fold => "Fold",
+                    Action::Check => "Check",
+                    Action::Call => "Call",
+                    Action::Bet(_) => "Bet",
+                    Action::Raise(_) => "Raise",
+                };
+                action(*seat, name, *amount, *all_in, Vec::new())
+            }
+            EventKind::Board { street, cards } => {
+                let street = match street {
+                    Street::Preflop => "Preflop",
+                    Street::Flop => "Flop",
+                    Street::Turn => "Turn",
+                    Street::River => "River",
+                };
+                rounds.push(Round {
+                    id: rounds.len() as u32,
+                    street: street.to_string(),
+                    cards: cards.iter().map(Card::to_string).collect(),
+                    actions: Vec::new(),
+                });
+                continue;
+            }
+            EventKind::Show { seat, cards, .. } => {
+                if rounds.last().unwrap().street != "Showdown" {
+                    rounds.push(Round { id: rounds.len() as u32, street: "Showdown".to_string(), ..Round::default() });
+                }
+                action(*seat, "Shows Cards", 0, false, vec![cards.0, cards.1])
+            }
+            EventKind::Uncalled { .. } => continue,
+            EventKind::Win { seat, pot, amount } => {
+                if !pots.iter().any(|ohh_pot| ohh_pot.number == *pot) {
+                    pots.push(OhhPot { number: *pot, ..OhhPot::default() });
+                }
+                let ohh_pot = pots.iter_mut().find(|ohh_pot| ohh_pot.number == *pot).unwrap();
+                ohh_pot.amount += *amount as f64;
+                ohh_pot.player_wins.push(PlayerWin { player_id: id(*seat), win_amount: *amount as f64, contributed_rake: 0. });
+                continue;
+            }
+        };
+
+        rounds.last_mut().unwrap().actions.push(action);
+    }
+
+    let mut number = 0;
+    for action in rounds.iter_mut().flat_map(|round| round.actions.iter_mut()) {
+        number += 1;
+        action.action_number = number;
+    }
+    pots.sort_by_key(|ohh_pot| ohh_pot.number);
+
+    let ohh = Ohh {
+        spec_version: SPEC_VERSION.to_string(),
+        site_name: SITE_NAME.to_string(),
+        network_name: SITE_NAME.to_string(),
+        internal_version: env!("CARGO_PKG_VERSION").to_string(),
+        tournament: false,
+        game_number: history.number.to_string(),
+        start_date_utc: format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            started.year, started.month, started.day, started.hour, started.minute, started.second
+        ),
+        table_name: SITE_NAME.to_string(),
+        game_type: "Holdem".to_string(),
+        bet_limit: BetLimit {
+            bet_type: match history.structure.as_str() {
+                "Pot-Limit" => "PL",
+                "Fixed-Limit" => "FL",
+                _ => "NL",
+            }
+            .to_string(),
+            bet_cap: 0.,
+        },
+        table_size: history.seat_count,
+        currency: CURRENCY.to_string(),
+        dealer_seat: history.button + 1,
+        small_blind_amount: history.blinds.small_blind as f64,
+        big_blind_amount: history.blinds.big_blind as f64,
+        ante_amount: match history.blinds.ante {
+            Ante::None => 0.,
+            Ante::Each(ante) | Ante::BigBlind(ante) => ante as f64,
+        },
+        flags: Vec::new(),
+        players: history
+            .seats
+            .iter()
+            .map(|record| OhhPlayer {
+                id: id(record.seat),
+                seat: record.seat + 1,
+                name: record.name.clone(),
+                starting_stack: record.stack as f64,
+                is_sitting_out: false,
+            })
+            .collect(),
+        rounds,
+        pots,
+    };
+
+    serde_json::to_string_pretty(&OhhFile { ohh }).unwrap()
+}
+
+// Hands separated by blank lines, as in files of several hands
+pub fn write_all(histories: &[HandHistory]) -> String {
+    histories.iter().map(|history| write(history) + "\n\n").collect()
+}
+
+pub fn save(histories: &[HandHistory], path: &Path) -> Result<(), OhhError> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    file.write_all(write_all(histories).as_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<HandHistory>, OhhError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/*
+    Read Hold'em hands in the Open Hand History format, one or more JSON objects as written
+    by write or other sites and tools. Game numbers must be numeric. Amounts with cents are
+    read in cents, times in UTC. Every hand is checked by replaying it, and the chips awarded
+    and raked must add up to the pots. Hands without pots are shown down to award them.
+    Actions which do not change the hand, like players sitting down or mucking, are skipped.
+*/
+pub fn parse(text: &str) -> Result<Vec<HandHistory>, OhhError> {
+    serde_json::Deserializer::from_str(text.trim_start_matches('\u{feff}'))
+        .into_iter::<OhhFile>()
+        .map(|file| read_hand(&file?.ohh))
+        .collect()
+}
+
+fn read_hand(ohh: &Ohh) -> Result<HandHistory, OhhError> {
+    if ohh.game_type != "Holdem" {
+        return Err(OhhError::Unsupported(format!("game type {}", ohh.game_type)));
+    }
+
+    // Money amounts with cents are read in cents, so all of the hand's amounts are whole
+    let amounts = [ohh.small_blind_amount, ohh.big_blind_amount, ohh.ante_amount]
+        .into_iter()
+        .chain(ohh.players.iter().map(|player| player.starting_stack))
+        .chain(ohh.rounds.iter().flat_map(|round| round.actions.iter().map(|action| action.amount)))
+        .chain(ohh.pots.iter().flat_map(|pot| [pot.amount, pot.rake, pot.jackpot]))
+        .chain(ohh.pots.iter().flat_map(|pot| pot.player_wins.iter().map(|win| win.win_amount)));
+    let scale = if amounts.clone().any(|amount| amount.fract() != 0.) { 100. } else { 1. };
+    let amount = |amount: f64| replay::chips(amount, scale).ok_or_else(|| OhhError::Invalid(format!("amount {amount}")));
+
+    let blinds = Blinds::new(amount(ohh.small_blind_amount)?, amount(ohh.big_blind_amount)?);
+    let structure: Box<dyn BettingStructure> = match ohh.bet_limit.bet_type.as_str() {
+        "NL" => Box::new(NoLimit),
+        "PL" => Box::new(PotLimit),
+        "FL" => Box::new(FixedLimit::new(blinds.big_blind)),
+        bet_type => return Err(OhhError::Unsupported(format!("bet type {bet_type}"))),
+    };
+
+    let mut seats: Vec<SeatRecord> = Vec::new();
+    let mut players: Vec<(u64, usize)> = Vec::new();
+
+    for player in ohh.players.iter().filter(|player| !player.is_sitting_out) {
+        if player.seat == 0 || player.seat > MAX_SEATS || seats.iter().any(|record| record.seat == player.seat - 1) {
+            return Err(OhhError::Invalid(format!("seat {} of {}", player.seat, player.name)));
+        }
+        if players.iter().any(|&(id, _)| id == player.id) {
+            return Err(OhhError::Invalid(format!("player id {} used twice", player.id)));
+        }
+
+        seats.push(SeatRecord { seat: player.seat - 1, name: player.name.clone(), stack: amount(player.starting_stack)? });
+        players.push((player.id, player.seat - 1));
+    }
+
+    let seat_count = ohh.table_size.max(ohh.dealer_seat).max(seats.iter().map(|record| record.seat + 1).max().unwrap_or(0));
+    if ohh.dealer_seat == 0 || seat_count > MAX_SEATS {
+        return Err(OhhError::Invalid(format!("dealer seat {} at a table of {}", ohh.dealer_seat, ohh.table_size)));
+    }
+
+    let started = parse_date(&ohh.start_date_utc).ok_or_else(|| OhhError::Invalid(format!("start date {}", ohh.start_date_utc)))?;
+    let number = ohh.game_number.parse().map_err(|_| OhhError::Invalid(format!("game number {}, hand numbers are numeric", ohh.game_number)))?;
+    let mut history = HandHistory::new(number, structure.name(), blinds, seat_count, ohh.dealer_seat - 1, seats);
+    history.started = started;
+
+    let mut replay = Replay::new(history, structure);
+    let seat = |id: u64| players.iter().find(|&&(player, _)| player == id).map(|&(_, seat)| seat).ok_or(OhhError::UnknownPlayer(id));
+
+    let mut showdown = false;
+    for round in &ohh.rounds {
+        let street = match round.street.as_str() {
+            "Preflop" => Street::Preflop,
+            "Flop" => Street::Flop,
+            "Turn" => Street::Turn,
+            "River" => Street::River,
+            "Showdown" => {
+                showdown = true;
+                replay.street()
+            }
+            street => return Err(OhhError::Unsupported(format!("street {street}"))),
+        };
+        if street < replay.street() || (showdown && round.street != "Showdown") {
+            return Err(OhhError::Invalid(format!("round {} out of order", round.street)));
+        }
+
+        if street > replay.street() {
+            replay.deal_board(street, parse_cards(&round.cards)?)?;
+        }
+
+        for action in &round.actions {
+            let seat = seat(action.player_id)?;
+            let amount = amount(action.amount)?;
+            read_action(&mut replay, seat, action, amount).map_err(|error| OhhError::Action { number: action.action_number, error })?;
+        }
+    }
+
+    // Hands without pots are shown down, and must be over with the chips awarded
+    if ohh.pots.is_empty() {
+        replay.award();
+
+        let history = replay.history();
+        if !history.is_complete() {
+            let expected = history.seats.iter().map(|record| history.invested(record.seat)).sum();
+            return Err(OhhError::Pots { awarded: 0, expected });
+        }
+
+        return Ok(replay.finish());
+    }
+
+    // Chips awarded and raked must add up to the pots, the uncalled bet following from the actions
+    let expected = replay.return_uncalled();
+    let mut awarded = 0;
+
+    for ohh_pot in &ohh.pots {
+        awarded += amount(ohh_pot.rake)? + amount(ohh_pot.jackpot)?;
+
+        for win in &ohh_pot.player_wins {
+            let (seat, amount) = (seat(win.player_id)?, amount(win.win_amount)?);
+
+            awarded += amount;
+            if amount > 0 {
+                replay.record(EventKind::Win { seat, pot: ohh_pot.number, amount });
+            }
+        }
+    }
+
+    if awarded != expected {
+        return Err(OhhError::Pots { awarded, expected });
+    }
+
+    Ok(replay.finish())
+}
+
+fn read_action(replay: &mut Replay, seat: usize, action: &OhhAction, amount: u32) -> Result<(), ReplayError> {
+    let post = match action.action.as_str() {
+        "Post SB" if replay.is_posted(Post::SmallBlind) => Some(Post::MissedSmallBlind),
+        "Post SB" => Some(Post::SmallBlind),
+        "Post BB" if replay.is_posted(Post::BigBlind) => Some(Post::MissedBigBlind),
+        "Post BB" => Some(Post::BigBlind),
+        "Straddle" => Some(Post::Straddle),
+        "Post Ante" => Some(Post::Ante),
+        "Post Dead" => Some(Post::MissedSmallBlind),
+        "Post Extra Blind" => Some(Post::MissedBigBlind),
+        _ => None,
+    };
+
+    if let Some(post) = post {
+        return replay.post(seat, post, amount);
+    }
+
+    let cards = || match parse_cards(&action.cards).as_deref() {
+        Ok(&[first, second]) => Ok((first, second)),
+        _ => Err(ReplayError::IllegalAction(format!("expected two cards, not {:?}", action.cards))),
+    };
+
+    let taken = match action.action.as_str() {
+        // Hidden cards of other players are left out
+        "Dealt Cards" if action.cards.iter().any(|card| card.contains(['?', 'x', 'X', '*'])) => return Ok(()),
+        "Dealt Cards" => return replay.deal(seat, cards()?),
+        "Shows Cards" => return replay.show(seat, cards()?),
+        "Fold" => Action::Fold,
+        "Check" => Action::Check,
+        "Call" => Action::Call,
+        "Bet" => Action::Bet(replay.bet(seat) + amount),
+        "Raise" => Action::Raise(replay.bet(seat) + amount),
+        // Anything else the player does, like sitting out or mucking, does not change the hand
+        _ => return Ok(()),
+    };
+
+    let put_in = replay.act(seat, taken)?;
+    if put_in != amount {
+        return Err(ReplayError::IllegalAction(format!("{} of {amount}, expected {put_in}", action.action)));
+    }
+
+    Ok(())
+}
+
+fn parse_cards(cards: &[String]) -> Result<Vec<Card>, OhhError> {
+    cards.iter().map(|card| card.parse::<Card>().map_err(|_| OhhError::Invalid(format!("card {card}")))).collect()
+}
+
+// "2026-10-19T12:00:00Z" or with an offset like "+02:00" from UTC, ignoring fractions of a second
+fn parse_date(text: &str) -> Option<SystemTime> {
+    // The date has dashes, so an offset's sign is searched for in the time only
+    let (date_time, offset) = match text.get(10..)?.rfind(['+', '-']) {
+        Some(sign) => text.split_at(10 + sign),
+        None => (text.trim_end_matches('Z'), ""),
+    };
+
+    let numbers: Vec<&str> = date_time.split(|c: char| !c.is_ascii_digit()).filter(|number| !number.is_empty()).take(6).collect();
+    let local = match numbers[..] {
+        [year, month, day, hour, minute, second] => {
+            DateTime::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?, hour.parse().ok()?, minute.parse().ok()?, second.parse().ok()?)?.to_system_time()
+        }
+        _ => return None,
+    };
+
+    // "+02:00", "+0200" or "+02"
+    let zone = offset.get(1..).unwrap_or("");
+    let (hours, minutes) = match zone.split_once(':') {
+        Some(parts) => parts,
+        None if zone.len() == 4 => zone.split_at(2),
+        None => (zone, "00"),
+    };
+    let seconds = match offset.is_empty() {
+        true => 0,
+        false if hours.len() == 2 && minutes.len() == 2 && (hours.to_string() + minutes).bytes().all(|byte| byte.is_ascii_digit()) => {
+            hours.parse::<u64>().ok()? * 3600 + minutes.parse::<u64>().ok()? * 60
+        }
+        false => return None,
+    };
+
+    match offset.starts_with('-') {
+        true => local.checked_add(Duration::from_secs(seconds)),
+        false => local.checked_sub(Duration::from_secs(seconds)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::tests::{as_written, played_hands};
+
+    #[test]
+    fn written_hands_are_read_back() {
+        let histories = played_hands(2, 150);
+
+        let parsed = parse(&write_all(&histories)).unwrap();
+        assert_eq!(parsed.len(), histories.len());
+        for (history, parsed) in histories.iter().zip(parsed) {
+            assert_eq!(as_written(history), parsed, "hand {}", history.number);
+        }
+    }
+
+    #[test]
+    fn hands_without_pots_are_shown_down() {
+        for history in played_hands(3, 50) {
+            let mut file: OhhFile = serde_json::from_str(&write(&history)).unwrap();
+            file.ohh.pots.clear();
+
+            let parsed = parse(&serde_json::to_string(&file).unwrap()).unwrap().remove(0);
+            assert!(parsed.is_complete(), "hand {}", history.number);
+            for record in &history.seats {
+                assert_eq!(parsed.collected(record.seat), history.collected(record.seat), "hand {}", history.number);
+            }
+        }
+    }
+
+    #[test]
+    fn start_dates_are_read_in_utc() {
+        let utc = DateTime::new(2026, 10, 19, 12, 30, 0).unwrap().to_system_time();
+
+        for text in ["2026-10-19T12:30:00Z", "2026-10-19T12:30:00.250Z", "2026-10-19T14:30:00+02:00", "2026-10-19T07:00:00-0530", "2026-10-19T13:30:00+01"] {
+            assert_eq!(parse_date(text), Some(utc), "{text}");
+        }
+        for text in ["2026-10-19T14:30:00+2:0", "2026-10-19T14:30:00+02:0", "2026-10-19T14:30:00+0a:00", "2026-10-19"] {
+            assert_eq!(parse_date(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn game_numbers_must_be_numeric() {
+        let mut file: OhhFile = serde_json::from_str(&write(&played_hands(4, 1)[0])).unwrap();
+        file.ohh.game_number = "A-1".to_string();
+
+        assert!(matches!(parse(&serde_json::to_string(&file).unwrap()), Err(OhhError::Invalid(_))));
+    }
+}
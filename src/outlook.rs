@@ -0,0 +1,123 @@
+// https://en.wikipedia.org/wiki/Texas_hold_%27em#Play_of_the_hand
+// https://en.wikipedia.org/wiki/Poker_probability_(Texas_hold_%27em)
+
+use std::{collections::HashMap, fmt};
+
+use rayon::prelude::*;
+
+use crate::{
+    card::Card,
+    equity::{for_each_board, remaining_cards},
+    evaluator::HandState,
+    game_logic::Ranking,
+    range::Combo,
+    simulation::Street,
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum OutlookError {
+    BoardLength(usize),
+    DuplicateCard(Card),
+}
+
+impl fmt::Display for OutlookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutlookError::BoardLength(length) => write!(f, "board has {length} cards, expected 0, 3, 4 or 5"),
+            OutlookError::DuplicateCard(card) => write!(f, "card {card} is used more than once"),
+        }
+    }
+}
+
+impl std::error::Error for OutlookError {}
+
+// How often each ranking is made at a street, over all ways the board can be dealt up to it
+#[derive(Clone, Debug)]
+pub struct StreetDistribution {
+    pub street: Street,
+    pub counts: HashMap<Ranking, u64>,
+    pub boards: u64,
+}
+
+impl StreetDistribution {
+    pub fn count(&self, ranking: Ranking) -> u64 {
+        self.counts.get(&ranking).copied().unwrap_or(0)
+    }
+
+    pub fn probability(&self, ranking: Ranking) -> f64 {
+        match self.boards {
+            0 => 0.,
+            boards => self.count(ranking) as f64 / boards as f64,
+        }
+    }
+
+    // Probability of ending up with at least the given ranking
+    pub fn probability_at_least(&self, ranking: Ranking) -> f64 {
+        Ranking::ALL.into_iter().filter(|&other| other >= ranking).map(|other| self.probability(other)).sum()
+    }
+}
+
+/*
+    Distribution of the ranking of the given hole cards at every street from the current one
+    (determined by the board length) to the river, computed exactly by enumerating every way
+    the rest of the board can be dealt. The last entry is the final distribution at the river.
+*/
+pub fn evolution(hole_cards: Combo, board: &[Card]) -> Result<Vec<StreetDistribution>, OutlookError> {
+    let current = match board.len() {
+        0 => Street::Preflop,
+        3 => Street::Flop,
+        4 => Street::Turn,
+        5 => Street::River,
+        length => return Err(OutlookError::BoardLength(length)),
+    };
+
+    let known: Vec<Card> = [hole_cards.0, hole_cards.1].into_iter().chain(board.iter().copied()).collect();
+    for (i, card) in known.iter().enumerate() {
+        if known[..i].iter().any(|other| other.is_same(card)) {
+            return Err(OutlookError::DuplicateCard(*card));
+        }
+    }
+
+    let remaining = remaining_cards(&known);
+
+    Ok([Street::Preflop, Street::Flop, Street::Turn, Street::River]
+        .into_iter()
+        .filter(|&street| street >= current)
+        .map(|street| distribution(hole_cards, board, &remaining, street))
+        .collect())
+}
+
+fn distribution(hole_cards: Combo, board: &[Card], remaining: &[Card], street: Street) -> StreetDistribution {
+    let missing = street.board_cards() - board.len();
+
+    let state = HandState::from_cards(board).with(hole_cards.0).with(hole_cards.1);
+
+    let counts = if missing == 0 {
+        HashMap::from([(state.rank().ranking(), 1)])
+    } else {
+        // Parallelized over the first missing card, like the equity enumeration
+        (0..remaining.len())
+            .into_par_iter()
+            .map(|first| {
+                let mut counts: HashMap<Ranking, u64> = HashMap::new();
+
+                for_each_board(&[state.with(remaining[first])], &remaining[first + 1..], missing - 1, &mut |states| {
+                    *counts.entry(states[0].rank().ranking()).or_insert(0) += 1;
+                });
+
+                counts
+            })
+            .reduce(HashMap::new, |mut counts, other| {
+                for (ranking, count) in other {
+                    *counts.entry(ranking).or_insert(0) += count;
+                }
+                counts
+            })
+    };
+
+    StreetDistribution {
+        street,
+        boards: counts.values().sum(),
+        counts,
+    }
+}
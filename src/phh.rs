@@ -0,0 +1,511 @@
+// https://arxiv.org/abs/2312.11753
+// https://github.com/uoftcprg/phh-std
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    betting::{BettingStructure, FixedLimit, NoLimit, DEFAULT_RAISE_CAP},
+    blinds::Blinds,
+    card::Card,
+    history::{DateTime, EventKind, HandHistory, Post, SeatRecord},
+    replay::{self, Replay, ReplayError},
+    simulation::Street,
+    table::{Action, MAX_SEATS},
+};
+
+#[derive(Debug)]
+pub enum PhhError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Unsupported(String),
+    Invalid(String),
+    // An action which could not have been taken, numbered from 1
+    Action { number: usize, error: ReplayError },
+    // The stack a player finished with differs from the one given
+    FinishingStack { player: String, expected: u32, actual: u32 },
+}
+
+impl fmt::Display for PhhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhhError::Io(error) => write!(f, "{error}"),
+            PhhError::Toml(error) => write!(f, "{error}"),
+            PhhError::Unsupported(message) => write!(f, "unsupported {message}"),
+            PhhError::Invalid(message) => write!(f, "invalid {message}"),
+            PhhError::Action { number, error } => write!(f, "action {number}: {error}"),
+            PhhError::FinishingStack { player, expected, actual } => write!(f, "{player} finishes with {actual}, expected {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for PhhError {}
+
+impl From<io::Error> for PhhError {
+    fn from(error: io::Error) -> Self {
+        PhhError::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for PhhError {
+    fn from(error: toml::de::Error) -> Self {
+        PhhError::Toml(error)
+    }
+}
+
+// The fields of the specification used here, amounts by player
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Phh {
+    variant: String,
+    antes: Vec<f64>,
+    blinds_or_straddles: Vec<f64>,
+    min_bet: f64,
+    small_bet: f64,
+    big_bet: f64,
+    starting_stacks: Vec<f64>,
+    actions: Vec<String>,
+    players: Vec<String>,
+    seats: Vec<usize>,
+    seat_count: usize,
+    hand: u64,
+    year: i64,
+    month: u32,
+    day: u32,
+    time: Option<toml::value::Datetime>,
+    time_zone: String,
+    finishing_stacks: Vec<f64>,
+}
+
+/*
+    Write a hand in the Poker Hand History format, as used for research datasets:
+
+        variant = "NT"
+        antes = [0, 0, 0]
+        blinds_or_straddles = [5, 10, 0]
+        min_bet = 10
+        starting_stacks = [1000, 1000, 1000]
+        actions = [
+            "d dh p1 AsKd",
+            ...
+            "p3 cbr 30",
+        ]
+
+    Players are numbered clockwise from the left of the button, which is the last player,
+    and heads-up the blinds are given small blind first. Dead blinds owed after sitting out
+    are written as antes and live ones as blinds, added to those the player posted, and the
+    hole cards of every player are written. Pot-limit Hold'em has no variant in the format.
+*/
+pub fn write(history: &HandHistory) -> Result<String, PhhError> {
+    let order = player_order(history);
+    let player = |seat: usize| order.iter().position(|record| record.seat == seat).unwrap() + 1;
+
+    let posted = |dead: bool| -> Vec<u32> {
+        order
+            .iter()
+            .map(|record| {
+                let posts = history.events.iter().filter_map(|event| match event.kind {
+                    EventKind::Post { seat, post, amount } if seat == record.seat && matches!(post, Post::Ante | Post::MissedSmallBlind) == dead => Some(amount),
+                    _ => None,
+                });
+                posts.sum()
+            })
+            .collect()
+    };
+
+    let mut blinds = posted(false);
+    if blinds.len() == 2 {
+        blinds.reverse();
+    }
+
+    let mut text = String::new();
+    let big_blind = history.blinds.big_blind;
+
+    match history.structure.as_str() {
+        "No-Limit" => writeln!(text, "variant = \"NT\"\nante_trimming_status = true").unwrap(),
+        "Fixed-Limit" => writeln!(text, "variant = \"FT\"\nante_trimming_status = true").unwrap(),
+        structure => return Err(PhhError::Unsupported(format!("{structure} Hold'em, which has no variant"))),
+    }
+    writeln!(text, "antes = {}", list(&posted(true))).unwrap();
+    writeln!(text, "blinds_or_straddles = {}", list(&blinds)).unwrap();
+    match history.structure.as_str() {
+        "No-Limit" => writeln!(text, "min_bet = {big_blind}").unwrap(),
+        _ => writeln!(text, "small_bet = {big_blind}\nbig_bet = {}", big_blind * 2).unwrap(),
+    }
+    writeln!(text, "starting_stacks = {}", list(&order.iter().map(|record| record.stack).collect::<Vec<u32>>())).unwrap();
+
+    writeln!(text, "actions = [").unwrap();
+    for event in &history.events {
+        let action = match &event.kind {
+            EventKind::Dealt { seat, cards } => format!("d dh p{} {}{}", player(*seat), cards.0, cards.1),
+            EventKind::Board { cards, .. } => format!("d db {}", cards.iter().map(Card::to_string).collect::<String>()),
+            EventKind::Action { seat, action, .. } => match action {
+                Action::Fold => format!("p{} f", player(*seat)),
+                Action::Check | Action::Call => format!("p{} cc", player(*seat)),
+                Action::Bet(total) | Action::Raise(total) => format!("p{} cbr {total}", player(*seat)),
+            },
+            EventKind::Show { seat, cards, .. } => format!("p{} sm {}{}", player(*seat), cards.0, cards.1),
+            // Posts are given by the antes and blinds, pots follow from the actions
+            EventKind::Post { .. } | EventKind::Uncalled { .. } | EventKind::Win { .. } => continue,
+        };
+        writeln!(text, "    \"{action}\",").unwrap();
+    }
+    writeln!(text, "]").unwrap();
+
+    let names: Vec<String> = order.iter().map(|record| toml::Value::String(record.name.clone()).to_string()).collect();
+    let seats: Vec<u32> = order.iter().map(|record| record.seat as u32 + 1).collect();
+    writeln!(text, "players = [{}]", names.join(", ")).unwrap();
+    writeln!(text, "seats = {}\nseat_count = {}\nhand = {}", list(&seats), history.seat_count, history.number).unwrap();
+
+    let started = DateTime::from_system_time(history.started);
+    writeln!(text, "year = {}\nmonth = {}\nday = {}", started.year, started.month, started.day).unwrap();
+    writeln!(text, "time = {:02}:{:02}:{:02}\ntime_zone = \"UTC\"", started.hour, started.minute, started.second).unwrap();
+
+    if history.is_complete() {
+        let finishing: Vec<u32> = order.iter().map(|record| record.stack - history.invested(record.seat) + history.collected(record.seat)).collect();
+        writeln!(text, "finishing_stacks = {}", list(&finishing)).unwrap();
+    }
+
+    Ok(text)
+}
+
+// Hands in the format for several hands, each in a table numbered from 1
+pub fn write_all(histories: &[HandHistory]) -> Result<String, PhhError> {
+    let mut text = String::new();
+    for (i, history) in histories.iter().enumerate() {
+        writeln!(text, "[{}]\n{}", i + 1, write(history)?).unwrap();
+    }
+
+    Ok(text)
+}
+
+pub fn save(histories: &[HandHistory], path: &Path) -> Result<(), PhhError> {
+    let text = match histories {
+        [history] => write(history)?,
+        histories => write_all(histories)?,
+    };
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<HandHistory>, PhhError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/*
+    Read no-limit and fixed-limit Hold'em hands in the Poker Hand History format, either one
+    hand or several in numbered tables. Amounts with cents are read in cents, and times must
+    be in UTC. The format has no blind sizes, button or pots, so the small blind is the first
+    blind smaller than the big blind, the big blind is the next one, a larger blind after it
+    is a straddle and any other blind a missed big blind. Antes posted by every player are
+    antes, otherwise only the big blind's is, and the rest are dead small blinds. The last
+    player has the button. Every hand is checked by replaying it, and the pots are awarded by
+    showing down the hands, which must leave players with the finishing stacks if given.
+*/
+pub fn parse(text: &str) -> Result<Vec<HandHistory>, PhhError> {
+    let text = text.trim_start_matches('\u{feff}');
+    let table: toml::Table = toml::from_str(text)?;
+
+    if table.contains_key("variant") {
+        return Ok(vec![read_straddle(&toml::from_str(text)?)?]);
+    }
+
+    let mut hands: Vec<(u64, Phh)> = Vec::new();
+    for (key, hand) in toml::from_str::<BTreeMap<String, Phh>>(text)? {
+        let number = key.parse().map_err(|_| PhhError::Invalid(format!("hand table [{key}]")))?;
+        hands.push((number, hand));
+    }
+    hands.sort_by_key(|(number, _)| *number);
+
+    hands.iter().map(|(_, hand)| read_straddle(hand)).collect()
+}
+
+/*
+    A player all-in for no more than the big blind after it either straddled short or posted
+    a missed big blind, whichever the actions replay with.
+*/
+fn read_straddle(phh: &Phh) -> Result<HandHistory, PhhError> {
+    read_hand(phh, false).or_else(|error| read_hand(phh, true).map_err(|_| error))
+}
+
+fn read_hand(phh: &Phh, all_in_straddle: bool) -> Result<HandHistory, PhhError> {
+    let players = phh.starting_stacks.len();
+    if !(2..=MAX_SEATS).contains(&players) {
+        return Err(PhhError::Invalid(format!("{players} players")));
+    }
+
+    let by_player = |amounts: &[f64], name: &str| match amounts.len() {
+        0 => Ok(vec![0.; players]),
+        length if length == players => Ok(amounts.to_vec()),
+        length => Err(PhhError::Invalid(format!("{length} {name} for {players} players"))),
+    };
+    let antes = by_player(&phh.antes, "antes")?;
+    let mut blinds = by_player(&phh.blinds_or_straddles, "blinds or straddles")?;
+    if players == 2 {
+        blinds.reverse();
+    }
+
+    // Money amounts with cents are read in cents, so all of the hand's amounts are whole
+    let bets: Vec<f64> = phh.actions.iter().filter_map(|action| action.split_once(" cbr ")?.1.split_whitespace().next()?.parse().ok()).collect();
+    let amounts = [phh.min_bet, phh.small_bet, phh.big_bet].into_iter().chain(antes.iter().chain(&blinds).chain(&phh.starting_stacks).chain(&phh.finishing_stacks).chain(&bets).copied());
+    let scale = if amounts.clone().any(|amount| amount.fract() != 0.) { 100. } else { 1. };
+    let amount = |amount: f64| replay::chips(amount, scale).ok_or_else(|| PhhError::Invalid(format!("amount {amount}")));
+
+    let antes = antes.into_iter().map(amount).collect::<Result<Vec<u32>, PhhError>>()?;
+    let blinds = blinds.into_iter().map(amount).collect::<Result<Vec<u32>, PhhError>>()?;
+
+    let (structure, big_blind): (Box<dyn BettingStructure>, u32) = match phh.variant.as_str() {
+        "NT" => (Box::new(NoLimit), amount(phh.min_bet)?),
+        "FT" => {
+            let (small_bet, big_bet) = (amount(phh.small_bet)?, amount(phh.big_bet)?);
+            (Box::new(FixedLimit { small_bet, big_bet, cap: DEFAULT_RAISE_CAP }), small_bet)
+        }
+        variant => return Err(PhhError::Unsupported(format!("variant {variant}"))),
+    };
+
+    let seats: Vec<usize> = match phh.seats.len() {
+        0 => (1..=players).collect(),
+        length if length == players => phh.seats.clone(),
+        length => return Err(PhhError::Invalid(format!("{length} seats for {players} players"))),
+    };
+    let seat_count = phh.seat_count.max(seats.iter().copied().max().unwrap());
+    if seats.iter().enumerate().any(|(i, &seat)| seat == 0 || seats[..i].contains(&seat)) || seat_count > MAX_SEATS {
+        return Err(PhhError::Invalid(format!("seats {seats:?} at a table of {seat_count}")));
+    }
+    let seat = |player: usize| seats[player] - 1;
+
+    /*
+        The small blind is the first blind smaller than the big blind with another after it,
+        and the big blind the next one. Heads-up the first player has the big blind.
+    */
+    let (small_blind, big_blind_player) = match players {
+        2 => (Some(1), 0),
+        _ => {
+            let small_blind = (0..players).find(|&player| blinds[player] > 0 && blinds[player] < big_blind && blinds[player + 1..].iter().any(|&blind| blind > 0));
+            let after = small_blind.map_or(0, |player| player + 1);
+            (small_blind, (after..players).find(|&player| blinds[player] > 0).unwrap_or(after % players))
+        }
+    };
+    let all_in = |player: usize| (u64::from(antes[player]) + u64::from(blinds[player])) as f64 >= phh.starting_stacks[player] * scale;
+    let straddle = Some(big_blind_player + 1).filter(|&player| player < players && players > 2 && (blinds[player] > big_blind || all_in_straddle && blinds[player] > 0 && all_in(player)));
+
+    /*
+        The ante most players not all-in from it posted, less for short stacks and more for
+        those owing a dead small blind.
+    */
+    let ante = antes.iter().all(|&ante| ante > 0).then(|| {
+        let mut counts: Vec<(u32, usize)> = Vec::new();
+        let short = |player: usize| f64::from(antes[player]) >= phh.starting_stacks[player] * scale;
+        let posted = (0..players).filter(|&player| !short(player)).map(|player| antes[player]);
+        for ante in posted.clone().next().map_or_else(|| antes.clone(), |_| posted.collect()) {
+            match counts.iter_mut().find(|(amount, _)| *amount == ante) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((ante, 1)),
+            }
+        }
+        counts.iter().max_by_key(|&&(amount, count)| (count, Reverse(amount))).unwrap().0
+    });
+
+    let mut records: Vec<SeatRecord> = Vec::new();
+    for (player, &stack) in phh.starting_stacks.iter().enumerate() {
+        let name = phh.players.get(player).cloned().unwrap_or_else(|| format!("p{}", player + 1));
+        records.push(SeatRecord { seat: seat(player), name, stack: amount(stack)? });
+    }
+    records.sort_by_key(|record| record.seat);
+
+    if !matches!(phh.time_zone.as_str(), "" | "UTC" | "GMT" | "Etc/UTC") {
+        return Err(PhhError::Unsupported(format!("time zone {}, only UTC is read", phh.time_zone)));
+    }
+
+    let started = match (phh.year, phh.time.and_then(|time| time.time)) {
+        (0, _) => UNIX_EPOCH,
+        (year, time) => {
+            let (hour, minute, second) = time.map(|time| (time.hour as u32, time.minute as u32, time.second as u32)).unwrap_or((0, 0, 0));
+            DateTime::new(year, phh.month, phh.day, hour, minute, second).map(|date_time| date_time.to_system_time()).ok_or_else(|| PhhError::Invalid("date".to_string()))?
+        }
+    };
+
+    let small_blind_amount = small_blind.map(|player| blinds[player]).filter(|&amount| amount > 0).unwrap_or(big_blind / 2);
+    let mut history = HandHistory::new(phh.hand, structure.name(), Blinds::new(small_blind_amount, big_blind), seat_count, seat(players - 1), records);
+    history.started = started;
+
+    let mut replay = Replay::new(history, structure);
+    let post = |replay: &mut Replay, player: usize, post: Post, amount: u32| replay.post(seat(player), post, amount).map_err(|error| PhhError::Action { number: 0, error });
+
+    // Posted in the order of the table: antes, blinds, straddle and then the blinds owed
+    let mut by_seat: Vec<usize> = (0..players).collect();
+    by_seat.sort_by_key(|&player| seat(player));
+
+    if let Some(ante) = ante {
+        for &player in &by_seat {
+            post(&mut replay, player, Post::Ante, antes[player].min(ante))?;
+        }
+    }
+    if let Some(player) = small_blind {
+        post(&mut replay, player, Post::SmallBlind, blinds[player])?;
+    }
+    post(&mut replay, big_blind_player, Post::BigBlind, blinds[big_blind_player])?;
+    if ante.is_none() {
+        post(&mut replay, big_blind_player, Post::Ante, antes[big_blind_player])?;
+    }
+    if let Some(player) = straddle {
+        post(&mut replay, player, Post::Straddle, blinds[player])?;
+    }
+    for &player in &by_seat {
+        if Some(player) != small_blind && player != big_blind_player && Some(player) != straddle {
+            post(&mut replay, player, Post::MissedBigBlind, blinds[player])?;
+        }
+
+        let dead = match ante {
+            Some(ante) => antes[player].saturating_sub(ante),
+            None if player != big_blind_player => antes[player],
+            None => 0,
+        };
+        post(&mut replay, player, Post::MissedSmallBlind, dead)?;
+    }
+
+    for (i, action) in phh.actions.iter().enumerate() {
+        let action = action.split('#').next().unwrap().trim();
+        let number = i + 1;
+
+        read_action(&mut replay, action, players, scale, &seat).map_err(|error| match error {
+            PhhError::Action { error, .. } => PhhError::Action { number, error },
+            error => error,
+        })?;
+    }
+
+    replay.award();
+
+    if !phh.finishing_stacks.is_empty() && replay.history().is_complete() {
+        let history = replay.history();
+
+        for (player, &expected) in phh.finishing_stacks.iter().enumerate() {
+            let record = history.player(seat(player)).ok_or_else(|| PhhError::Invalid(format!("{} finishing stacks", phh.finishing_stacks.len())))?;
+            let (expected, actual) = (amount(expected)?, record.stack - history.invested(record.seat) + history.collected(record.seat));
+            if expected != actual {
+                return Err(PhhError::FinishingStack { player: record.name.clone(), expected, actual });
+            }
+        }
+    }
+
+    Ok(replay.finish())
+}
+
+fn read_action(replay: &mut Replay, action: &str, players: usize, scale: f64, seat: &dyn Fn(usize) -> usize) -> Result<(), PhhError> {
+    let invalid = || PhhError::Invalid(format!("action {action}"));
+    let replayed = |error: ReplayError| PhhError::Action { number: 0, error };
+
+    let tokens: Vec<&str> = action.split_whitespace().collect();
+    let player = |token: &str| {
+        token
+            .strip_prefix('p')
+            .and_then(|number| number.parse::<usize>().ok())
+            .filter(|number| (1..=players).contains(number))
+            .map(|number| seat(number - 1))
+            .ok_or_else(invalid)
+    };
+
+    match tokens[..] {
+        ["d", "dh", dealt, cards] => match parse_cards(cards)? {
+            // Hidden cards of other players are left out
+            None => Ok(()),
+            Some(cards) => match cards[..] {
+                [first, second] => replay.deal(player(dealt)?, (first, second)).map_err(replayed),
+                _ => Err(PhhError::Unsupported(format!("{} hole cards", cards.len()))),
+            },
+        },
+        ["d", "db", cards] => {
+            let cards = parse_cards(cards)?.ok_or_else(invalid)?;
+            let street = match replay.board().len() + cards.len() {
+                3 => Street::Flop,
+                4 => Street::Turn,
+                5 => Street::River,
+                count => return Err(replayed(ReplayError::Board { street: replay.street(), cards: count })),
+            };
+            replay.deal_board(street, cards).map_err(replayed)
+        }
+        [acting, "f"] => replay.act(player(acting)?, Action::Fold).map(|_| ()).map_err(replayed),
+        [acting, "cc"] => {
+            let seat = player(acting)?;
+            let action = if replay.bet(seat) < replay.current_bet() { Action::Call } else { Action::Check };
+            replay.act(seat, action).map(|_| ()).map_err(replayed)
+        }
+        [acting, "cbr", total] => {
+            let seat = player(acting)?;
+            let total = total.parse::<f64>().ok().and_then(|total| replay::chips(total, scale)).ok_or_else(invalid)?;
+            let action = if replay.current_bet() == 0 { Action::Bet(total) } else { Action::Raise(total) };
+            replay.act(seat, action).map(|_| ()).map_err(replayed)
+        }
+        // Mucked hands are not shown
+        [_, "sm"] => Ok(()),
+        [acting, "sm", cards] => match parse_cards(cards)?.as_deref() {
+            None => Ok(()),
+            Some(&[first, second]) => replay.show(player(acting)?, (first, second)).map_err(replayed),
+            Some(_) => Err(invalid()),
+        },
+        _ => Err(PhhError::Unsupported(format!("action {action}"))),
+    }
+}
+
+// Players clockwise from the left of the button, ending with the button
+fn player_order(history: &HandHistory) -> Vec<&SeatRecord> {
+    let mut order: Vec<&SeatRecord> = history.seats.iter().collect();
+    order.sort_by_key(|record| (record.seat + history.seat_count - history.button - 1) % history.seat_count);
+    order
+}
+
+fn list(amounts: &[u32]) -> String {
+    format!("[{}]", amounts.iter().map(u32::to_string).collect::<Vec<String>>().join(", "))
+}
+
+// Cards written together like "AsKd", none if hidden as "????"
+fn parse_cards(cards: &str) -> Result<Option<Vec<Card>>, PhhError> {
+    if cards.contains('?') {
+        return Ok(None);
+    }
+
+    let chars: Vec<char> = cards.chars().collect();
+    chars
+        .chunks(2)
+        .map(|card| card.iter().collect::<String>().parse::<Card>().map_err(|_| PhhError::Invalid(format!("cards {cards}"))))
+        .collect::<Result<Vec<Card>, PhhError>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::tests::{as_written, played_hands};
+
+    #[test]
+    fn written_hands_are_read_back() {
+        let histories: Vec<HandHistory> = played_hands(5, 150).into_iter().filter(|history| history.structure != "Pot-Limit").collect();
+
+        let parsed = parse(&write_all(&histories).unwrap()).unwrap();
+        assert_eq!(parsed.len(), histories.len());
+        for (history, parsed) in histories.iter().zip(parsed) {
+            assert_eq!(as_written(history), parsed, "hand {}", history.number);
+        }
+    }
+
+    #[test]
+    fn pot_limit_hands_are_not_written() {
+        let history = played_hands(5, 2).remove(1);
+
+        assert_eq!(history.structure, "Pot-Limit");
+        assert!(matches!(write(&history), Err(PhhError::Unsupported(_))));
+    }
+}
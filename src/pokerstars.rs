@@ -0,0 +1,753 @@
+// https://en.wikipedia.org/wiki/Hand_history
+// https://www.pokerstars.com/help/articles/hand-history/
+
+use std::{
+    cmp::Reverse,
+    fmt::{self, Write as _},
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    blinds::Blinds,
+    card::Card,
+    equity::showdown_strength,
+    history::{DateTime, EventKind, HandHistory, Post, SeatRecord},
+    simulation::Street,
+    table::{Action, MAX_SEATS},
+};
+
+// Name written for the table, which the hand histories do not record
+pub const TABLE_NAME: &str = "rust-texas-holdem";
+
+#[derive(Debug)]
+pub enum PokerStarsError {
+    Io(io::Error),
+    Parse(usize, String),
+}
+
+impl fmt::Display for PokerStarsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PokerStarsError::Io(error) => write!(f, "{error}"),
+            PokerStarsError::Parse(line, message) => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PokerStarsError {}
+
+impl From<io::Error> for PokerStarsError {
+    fn from(error: io::Error) -> Self {
+        PokerStarsError::Io(error)
+    }
+}
+
+/*
+    Write a hand in the PokerStars text format, as read by hand history tracking tools:
+
+        PokerStars Hand #1: Hold'em No Limit (5/10) - 2026/10/19 12:00:00 UTC
+        Table 'rust-texas-holdem' 6-max Seat #1 is the button
+        Seat 1: Alice (1000 in chips)
+        Bob: posts small blind 5
+        *** HOLE CARDS ***
+        Dealt to Alice [Ah Kd]
+        Alice: raises 20 to 30
+        ...
+        *** SUMMARY ***
+
+    Seats are numbered from 1, and the hole cards of every player are written.
+*/
+pub fn write(history: &HandHistory) -> String {
+    let mut text = String::new();
+    let name = |seat: usize| history.player(seat).map(|record| record.name.as_str()).unwrap_or("?");
+    let pot_count = history.events.iter().filter_map(|event| match event.kind {
+        EventKind::Win { pot, .. } => Some(pot + 1),
+        _ => None,
+    });
+    let pot_count = pot_count.max().unwrap_or(1);
+    let pot_name = |pot: usize| match (pot_count, pot) {
+        (1, _) => "pot".to_string(),
+        (_, 0) => "main pot".to_string(),
+        (_, pot) => format!("side pot-{pot}"),
+    };
+
+    let started = DateTime::from_system_time(history.started);
+    let structure = match history.structure.as_str() {
+        "Pot-Limit" => "Pot Limit",
+        "Fixed-Limit" => "Limit",
+        _ => "No Limit",
+    };
+
+    writeln!(
+        text,
+        "PokerStars Hand #{}: Hold'em {structure} ({}/{}) - {}/{:02}/{:02} {}:{:02}:{:02} UTC",
+        history.number, history.blinds.small_blind, history.blinds.big_blind, started.year, started.month, started.day, started.hour, started.minute, started.second
+    )
+    .unwrap();
+    writeln!(text, "Table '{TABLE_NAME}' {}-max Seat #{} is the button", history.seat_count, history.button + 1).unwrap();
+
+    for record in &history.seats {
+        writeln!(text, "Seat {}: {} ({} in chips)", record.seat + 1, record.name, record.stack).unwrap();
+    }
+
+    // The uncalled bet is returned right after the last bet, before any board cards are run out
+    let returned = history.events.iter().rposition(|event| matches!(event.kind, EventKind::Action { .. } | EventKind::Post { .. }));
+    let uncalled = history.events.iter().find_map(|event| match event.kind {
+        EventKind::Uncalled { seat, amount } => Some((seat, amount)),
+        _ => None,
+    });
+
+    let mut bets = vec![0; history.seat_count];
+    let mut current_bet = 0;
+    let mut board: Vec<Card> = Vec::new();
+    let (mut hole_cards, mut showdown) = (false, false);
+
+    for (i, event) in history.events.iter().enumerate() {
+        match &event.kind {
+            EventKind::Post { seat, post, amount } => {
+                // Both missed blinds are posted together, the small blind following the big blind
+                let missed_small_blind = history.events.get(i + 1).and_then(|next| match next.kind {
+                    EventKind::Post { seat: poster, post: Post::MissedSmallBlind, amount } if poster == *seat => Some(amount),
+                    _ => None,
+                });
+                let previous = i.checked_sub(1).map(|previous| &history.events[previous].kind);
+
+                match (post, missed_small_blind) {
+                    (Post::MissedBigBlind, Some(dead)) => writeln!(text, "{}: posts small & big blinds {}", name(*seat), amount + dead).unwrap(),
+                    (Post::MissedSmallBlind, _) if matches!(previous, Some(EventKind::Post { seat: poster, post: Post::MissedBigBlind, .. }) if poster == seat) => {}
+                    _ => {
+                        let posts = match post {
+                            Post::SmallBlind | Post::MissedSmallBlind => "small blind",
+                            Post::BigBlind | Post::MissedBigBlind => "big blind",
+                            Post::Straddle => "straddle",
+                            Post::Ante => "the ante",
+                        };
+                        writeln!(text, "{}: posts {posts} {amount}", name(*seat)).unwrap();
+                    }
+                }
+
+                if !matches!(post, Post::Ante | Post::MissedSmallBlind) {
+                    bets[*seat] += amount;
+                    current_bet = current_bet.max(bets[*seat]);
+                }
+            }
+            EventKind::Dealt { seat, cards } => {
+                if !hole_cards {
+                    writeln!(text, "*** HOLE CARDS ***").unwrap();
+                    hole_cards = true;
+                }
+                writeln!(text, "Dealt to {} [{} {}]", name(*seat), cards.0, cards.1).unwrap();
+            }
+            EventKind::Action { seat, action, amount, all_in, .. } => {
+                let action = match action {
+                    Action::Fold => "folds".to_string(),
+                    Action::Check => "checks".to_string(),
+                    Action::Call => format!("calls {amount}"),
+                    Action::Bet(to) => format!("bets {to}"),
+                    Action::Raise(to) => format!("raises {} to {to}", to - current_bet),
+                };
+                let all_in = if *all_in { " and is all-in" } else { "" };
+                writeln!(text, "{}: {action}{all_in}", name(*seat)).unwrap();
+
+                bets[*seat] += amount;
+                current_bet = current_bet.max(bets[*seat]);
+            }
+            EventKind::Board { street, cards } => {
+                let header = match street {
+                    Street::Flop => "FLOP",
+                    Street::Turn => "TURN",
+                    _ => "RIVER",
+                };
+                match board.is_empty() {
+                    true => writeln!(text, "*** {header} *** [{}]", join(cards)).unwrap(),
+                    false => writeln!(text, "*** {header} *** [{}] [{}]", join(&board), join(cards)).unwrap(),
+                }
+
+                board.extend(cards);
+                bets.iter_mut().for_each(|bet| *bet = 0);
+                current_bet = 0;
+            }
+            EventKind::Show { seat, cards, strength } => {
+                if !showdown {
+                    writeln!(text, "*** SHOW DOWN ***").unwrap();
+                    showdown = true;
+                }
+                writeln!(text, "{}: shows [{} {}] ({})", name(*seat), cards.0, cards.1, strength.ranking).unwrap();
+            }
+            EventKind::Uncalled { .. } => {}
+            EventKind::Win { seat, pot, amount } => writeln!(text, "{} collected {amount} from {}", name(*seat), pot_name(*pot)).unwrap(),
+        }
+
+        if let (Some((seat, amount)), true) = (uncalled, returned == Some(i)) {
+            writeln!(text, "Uncalled bet ({amount}) returned to {}", name(seat)).unwrap();
+        }
+    }
+
+    let mut pots = vec![0; pot_count];
+    for event in &history.events {
+        if let EventKind::Win { pot, amount, .. } = event.kind {
+            pots[pot] += amount;
+        }
+    }
+
+    writeln!(text, "*** SUMMARY ***").unwrap();
+    match pots.len() {
+        1 => writeln!(text, "Total pot {} | Rake 0", pots[0]).unwrap(),
+        _ => {
+            let side_pots: Vec<String> = pots[1..].iter().enumerate().map(|(i, amount)| format!("Side pot-{} {amount}.", i + 1)).collect();
+            writeln!(text, "Total pot {} Main pot {}. {} | Rake 0", pots.iter().sum::<u32>(), pots[0], side_pots.join(" ")).unwrap();
+        }
+    }
+    if !board.is_empty() {
+        writeln!(text, "Board [{}]", join(&board)).unwrap();
+    }
+
+    for record in &history.seats {
+        writeln!(text, "Seat {}: {}{} {}", record.seat + 1, record.name, position(history, record.seat), outcome(history, record.seat)).unwrap();
+    }
+
+    text
+}
+
+fn join(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+fn position(history: &HandHistory, seat: usize) -> String {
+    let posted = |blind: Post| history.events.iter().any(|event| matches!(event.kind, EventKind::Post { seat: poster, post, .. } if poster == seat && post == blind));
+
+    let mut position = String::new();
+    if history.button == seat {
+        position += " (button)";
+    }
+    if posted(Post::SmallBlind) {
+        position += " (small blind)";
+    }
+    if posted(Post::BigBlind) {
+        position += " (big blind)";
+    }
+    position
+}
+
+fn outcome(history: &HandHistory, seat: usize) -> String {
+    let won: u32 = history
+        .events
+        .iter()
+        .map(|event| match event.kind {
+            EventKind::Win { seat: winner, amount, .. } if winner == seat => amount,
+            _ => 0,
+        })
+        .sum();
+
+    let shown = history.events.iter().find_map(|event| match &event.kind {
+        EventKind::Show { seat: shower, cards, strength } if *shower == seat => Some((cards, strength)),
+        _ => None,
+    });
+
+    let folded = history.events.iter().find_map(|event| match event.kind {
+        EventKind::Action { street, seat: actor, action: Action::Fold, .. } if actor == seat => Some(street),
+        _ => None,
+    });
+
+    match (shown, folded) {
+        (Some((cards, strength)), _) if won > 0 => format!("showed [{} {}] and won ({won}) with {}", cards.0, cards.1, strength.ranking),
+        (Some((cards, strength)), _) => format!("showed [{} {}] and lost with {}", cards.0, cards.1, strength.ranking),
+        (None, Some(Street::Preflop)) if history.invested(seat) == 0 => "folded before Flop (didn't bet)".to_string(),
+        (None, Some(Street::Preflop)) => "folded before Flop".to_string(),
+        (None, Some(street)) => format!("folded on the {street:?}"),
+        (None, None) if won > 0 => format!("collected ({won})"),
+        (None, None) => "mucked".to_string(),
+    }
+}
+
+// Hands separated by blank lines, as in the files written by the PokerStars client
+pub fn write_all(histories: &[HandHistory]) -> String {
+    histories.iter().map(write).collect::<Vec<String>>().join("\n\n")
+}
+
+pub fn save(histories: &[HandHistory], path: &Path) -> Result<(), PokerStarsError> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    file.write_all(write_all(histories).as_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<HandHistory>, PokerStarsError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/*
+    Read Hold'em hands in the PokerStars text format, as written by write or the PokerStars
+    client. Amounts in a currency are read in cents, tournament and play money chips as they
+    are. Times are converted to UTC from the time zone given. Lines which do not change the
+    hand, like chat and players joining or leaving, are skipped.
+*/
+pub fn parse(text: &str) -> Result<Vec<HandHistory>, PokerStarsError> {
+    let lines: Vec<(usize, &str)> = text.trim_start_matches('\u{feff}').lines().enumerate().map(|(i, line)| (i + 1, line.trim())).collect();
+
+    let mut starts: Vec<usize> = lines.iter().enumerate().filter(|(_, (_, line))| line.starts_with("PokerStars ")).map(|(i, _)| i).collect();
+    starts.push(lines.len());
+
+    if let Some(&(number, _)) = lines[..starts[0]].iter().find(|(_, line)| !line.is_empty()) {
+        return Err(PokerStarsError::Parse(number, "expected a PokerStars hand header".to_string()));
+    }
+
+    starts.windows(2).map(|hand| parse_hand(&lines[hand[0]..hand[1]])).collect()
+}
+
+// Amounts and the current bets while reading the events of a hand
+struct Parser {
+    history: HandHistory,
+    cents: bool,
+    bets: Vec<u32>,
+    board: Vec<Card>,
+    // Hands shown before the board ran out in all-in hands, evaluated once it is complete
+    early_shows: Vec<(usize, (Card, Card))>,
+    summary: bool,
+}
+
+fn parse_hand(lines: &[(usize, &str)]) -> Result<HandHistory, PokerStarsError> {
+    let (number, header) = lines[0];
+    let error = |line: usize, message: &str| PokerStarsError::Parse(line, message.to_string());
+
+    let hand_number = header
+        .split_once('#')
+        .and_then(|(_, rest)| rest.split(':').next())
+        .and_then(|number| number.trim().parse().ok())
+        .ok_or_else(|| error(number, "missing hand number"))?;
+
+    let game = header.split_once("Hold'em ").map(|(_, game)| game).ok_or_else(|| error(number, "only Hold'em is supported"))?;
+    let structure = match game {
+        game if game.starts_with("No Limit") => "No-Limit",
+        game if game.starts_with("Pot Limit") => "Pot-Limit",
+        game if game.starts_with("Limit") => "Fixed-Limit",
+        _ => return Err(error(number, "unknown betting structure")),
+    };
+
+    // The stakes are the first parenthesized pair, after the buy-in and level in tournaments
+    let (stakes, after_stakes) = header
+        .match_indices('(')
+        .filter_map(|(start, _)| {
+            let end = start + header[start..].find(')')?;
+            header[start + 1..end].contains('/').then(|| (&header[start + 1..end], &header[end..]))
+        })
+        .next()
+        .ok_or_else(|| error(number, "missing stakes"))?;
+
+    let cents = stakes.contains(['$', '€', '£', '.']);
+    let (small_blind, big_blind) = stakes
+        .split_once('/')
+        .and_then(|(small, big)| Some((parse_amount(small, cents)?, parse_amount(big.split_whitespace().next()?, cents)?)))
+        .ok_or_else(|| error(number, "invalid stakes"))?;
+
+    let started = parse_date(after_stakes).ok_or_else(|| error(number, "missing or invalid date or time zone"))?;
+
+    let (table_number, table_line) = *lines.get(1).filter(|(_, line)| line.starts_with("Table ")).ok_or_else(|| error(number + 1, "expected the table line"))?;
+    let after_name = table_line.rsplit_once('\'').map(|(_, rest)| rest).unwrap_or(table_line);
+    let max_seats = after_name.split_whitespace().find_map(|token| token.strip_suffix("-max")?.parse::<usize>().ok());
+    let button = after_name
+        .split_once("Seat #")
+        .and_then(|(_, rest)| rest.split_whitespace().next()?.parse::<usize>().ok())
+        .filter(|&button| button >= 1)
+        .ok_or_else(|| error(table_number, "missing button seat"))?;
+
+    let mut seats: Vec<SeatRecord> = Vec::new();
+    let mut rest = &lines[2..];
+
+    while let Some(&(number, line)) = rest.first() {
+        let Some((seat, player)) = line.strip_prefix("Seat ").and_then(|line| line.split_once(": ")) else {
+            break;
+        };
+
+        let seat: usize = seat.parse().map_err(|_| error(number, "invalid seat number"))?;
+        let (name, chips) = player.rsplit_once(" (").filter(|(_, chips)| chips.contains("in chips")).ok_or_else(|| error(number, "missing stack"))?;
+        let stack = chips.split_whitespace().next().and_then(|amount| parse_amount(amount, cents)).ok_or_else(|| error(number, "invalid stack"))?;
+
+        if seat == 0 || seat > MAX_SEATS {
+            return Err(error(number, "seat number out of range"));
+        }
+        if !chips.ends_with("is sitting out") {
+            seats.push(SeatRecord { seat: seat - 1, name: name.to_string(), stack });
+        }
+
+        rest = &rest[1..];
+    }
+
+    let seat_count = max_seats.unwrap_or(0).max(button).max(seats.iter().map(|record| record.seat + 1).max().unwrap_or(0));
+    if seat_count > MAX_SEATS {
+        return Err(error(table_number, "too many seats"));
+    }
+
+    let mut history = HandHistory::new(hand_number, structure, Blinds::new(small_blind, big_blind), seat_count, button - 1, seats);
+    history.started = started;
+
+    let mut parser = Parser {
+        history,
+        cents,
+        bets: vec![0; seat_count],
+        board: Vec::new(),
+        early_shows: Vec::new(),
+        summary: false,
+    };
+
+    for &(number, line) in rest {
+        parser.line(line).map_err(|message| error(number, &message))?;
+    }
+
+    parser.show_early_shows();
+
+    let mut history = parser.history;
+    for event in history.events.iter_mut() {
+        event.time = history.started;
+    }
+
+    history.blinds = history.posted_blinds();
+
+    Ok(history)
+}
+
+impl Parser {
+    fn line(&mut self, line: &str) -> Result<(), String> {
+        if self.summary || line.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(section) = line.strip_prefix("*** ") {
+            return self.section(section);
+        }
+
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let (name, cards) = rest.split_once(" [").ok_or("missing hole cards")?;
+            let seat = self.seat(name).ok_or_else(|| format!("unknown player {name}"))?;
+            let cards = parse_cards(cards)?;
+
+            let [first, second] = cards[..] else {
+                return Err("expected two hole cards".to_string());
+            };
+            self.history.record(EventKind::Dealt { seat, cards: (first, second) });
+            return Ok(());
+        }
+
+        if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = rest.split_once(") returned to ").ok_or("invalid uncalled bet")?;
+            let amount = parse_amount(amount, self.cents).ok_or("invalid amount")?;
+            let seat = self.seat(name).ok_or_else(|| format!("unknown player {name}"))?;
+
+            self.history.record(EventKind::Uncalled { seat, amount });
+            return Ok(());
+        }
+
+        // Lines starting with a player name, longer names first so a name may contain another
+        let mut players: Vec<(usize, String)> = self.history.seats.iter().map(|record| (record.seat, record.name.clone())).collect();
+        players.sort_by_key(|(_, name)| Reverse(name.len()));
+
+        for (seat, name) in players {
+            if let Some(action) = line.strip_prefix(name.as_str()).and_then(|rest| rest.strip_prefix(": ")) {
+                return self.action(seat, action);
+            }
+
+            if let Some(collected) = line.strip_prefix(name.as_str()).and_then(|rest| rest.strip_prefix(" collected ")) {
+                let (amount, pot) = collected.split_once(" from ").ok_or("invalid collected line")?;
+                let amount = parse_amount(amount, self.cents).ok_or("invalid amount")?;
+                let pot = match pot {
+                    "pot" | "main pot" => 0,
+                    "side pot" => 1,
+                    pot => pot.strip_prefix("side pot-").and_then(|pot| pot.parse().ok()).ok_or("invalid pot")?,
+                };
+
+                self.history.record(EventKind::Win { seat, pot, amount });
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn section(&mut self, section: &str) -> Result<(), String> {
+        let (name, cards) = section.split_once(" ***").ok_or("invalid section")?;
+
+        let street = match name {
+            "HOLE CARDS" | "SHOW DOWN" => return Ok(()),
+            "SUMMARY" => {
+                self.summary = true;
+                return Ok(());
+            }
+            "FLOP" => Street::Flop,
+            "TURN" => Street::Turn,
+            "RIVER" => Street::River,
+            name => return Err(format!("unsupported section {name}")),
+        };
+
+        let cards = parse_cards(cards.rsplit_once('[').map(|(_, cards)| cards).ok_or("missing board cards")?)?;
+        if self.board.len() + cards.len() != street.board_cards() {
+            return Err(format!("expected {} board cards", street.board_cards()));
+        }
+
+        self.board.extend(&cards);
+        self.bets.iter_mut().for_each(|bet| *bet = 0);
+        self.history.record(EventKind::Board { street, cards });
+
+        if self.board.len() == 5 {
+            self.show_early_shows();
+        }
+
+        Ok(())
+    }
+
+    fn show_early_shows(&mut self) {
+        for (seat, cards) in std::mem::take(&mut self.early_shows) {
+            let strength = showdown_strength(cards, &self.board);
+            self.history.record(EventKind::Show { seat, cards, strength });
+        }
+    }
+
+    fn action(&mut self, seat: usize, action: &str) -> Result<(), String> {
+        let (action, all_in) = match action.strip_suffix(" and is all-in") {
+            Some(action) => (action, true),
+            None => (action, false),
+        };
+        let amount = |text: &str| parse_amount(text, self.cents).ok_or_else(|| format!("invalid amount {text}"));
+        let street = match self.board.len() {
+            0 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River,
+        };
+
+        let (action, put_in) = match action.split_whitespace().collect::<Vec<&str>>()[..] {
+            ["posts", "small", "&", "big", "blinds", total] => {
+                let total = amount(total)?;
+                let big_blind = total.min(self.history.blinds.big_blind);
+                self.post(seat, Post::MissedBigBlind, big_blind);
+                self.post(seat, Post::MissedSmallBlind, total - big_blind);
+                return Ok(());
+            }
+            ["posts", "small", "blind", posted] => {
+                let post = if self.posted(Post::SmallBlind) { Post::MissedSmallBlind } else { Post::SmallBlind };
+                self.post(seat, post, amount(posted)?);
+                return Ok(());
+            }
+            ["posts", "big", "blind", posted] => {
+                let post = if self.posted(Post::BigBlind) { Post::MissedBigBlind } else { Post::BigBlind };
+                self.post(seat, post, amount(posted)?);
+                return Ok(());
+            }
+            ["posts", "the", "ante", posted] => {
+                self.post(seat, Post::Ante, amount(posted)?);
+                return Ok(());
+            }
+            ["posts", "straddle", posted] => {
+                self.post(seat, Post::Straddle, amount(posted)?);
+                return Ok(());
+            }
+            ["shows", ..] => {
+                let cards = action.split_once('[').and_then(|(_, cards)| cards.split_once(']')).ok_or("missing shown cards")?;
+                let [first, second] = parse_cards(cards.0)?[..] else {
+                    return Err("expected two shown cards".to_string());
+                };
+                if self.board.len() < 5 {
+                    self.early_shows.push((seat, (first, second)));
+                    return Ok(());
+                }
+
+                let strength = showdown_strength((first, second), &self.board);
+                self.history.record(EventKind::Show { seat, cards: (first, second), strength });
+                return Ok(());
+            }
+            ["folds", ..] => (Action::Fold, 0),
+            ["checks"] => (Action::Check, 0),
+            ["calls", called] => (Action::Call, amount(called)?),
+            ["bets", bet] => {
+                let bet = amount(bet)?;
+                (Action::Bet(self.bets[seat] + bet), bet)
+            }
+            ["raises", _, "to", total] => {
+                let total = amount(total)?;
+                (Action::Raise(total), total.checked_sub(self.bets[seat]).ok_or("raise to less than the current bet")?)
+            }
+            // Anything else the player does, like sitting out or timing out, does not change the hand
+            _ => return Ok(()),
+        };
+
+        self.bets[seat] += put_in;
+        self.history.record(EventKind::Action { street, seat, action, amount: put_in, all_in });
+
+        Ok(())
+    }
+
+    fn post(&mut self, seat: usize, post: Post, amount: u32) {
+        if !matches!(post, Post::Ante | Post::MissedSmallBlind) {
+            self.bets[seat] += amount;
+        }
+        if amount > 0 {
+            self.history.record(EventKind::Post { seat, post, amount });
+        }
+    }
+
+    fn posted(&self, blind: Post) -> bool {
+        self.history.events.iter().any(|event| matches!(event.kind, EventKind::Post { post, .. } if post == blind))
+    }
+
+    fn seat(&self, name: &str) -> Option<usize> {
+        self.history.seats.iter().find(|record| record.name == name).map(|record| record.seat)
+    }
+}
+
+// An amount of chips, or of money in cents like "$1.05"
+fn parse_amount(text: &str, cents: bool) -> Option<u32> {
+    let text = text.trim().trim_start_matches(['$', '€', '£']).replace(',', "");
+
+    match cents {
+        true => text.parse::<f64>().ok().filter(|amount| *amount >= 0.).map(|amount| (amount * 100.).round() as u32),
+        false => text.parse().ok(),
+    }
+}
+
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    text.trim_end_matches(']').split_whitespace().map(|card| card.parse::<Card>().map_err(|error| error.to_string())).collect()
+}
+
+/*
+    The first "2026/10/19 12:00:00 CET" in the text in a known time zone, converted to UTC.
+    A date without a zone is read as UTC. PokerStars gives the time in the player's zone
+    followed by "[2026/10/19 6:00:00 ET]", Eastern Time with daylight saving.
+*/
+fn parse_date(text: &str) -> Option<SystemTime> {
+    let tokens: Vec<&str> = text.split_whitespace().map(|token| token.trim_start_matches('[').trim_end_matches(']')).collect();
+
+    (0..tokens.len().saturating_sub(1)).find_map(|i| {
+        let date: Vec<&str> = tokens[i].split('/').collect();
+        let time: Vec<&str> = tokens[i + 1].split(':').collect();
+
+        let local = match (&date[..], &time[..]) {
+            ([year, month, day], [hour, minute, second]) => {
+                DateTime::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?, hour.parse().ok()?, minute.parse().ok()?, second.parse().ok()?)?
+            }
+            _ => return None,
+        };
+
+        let offset = match tokens.get(i + 2).filter(|zone| zone.chars().all(|c| c.is_ascii_uppercase())) {
+            Some(zone) => zone_offset(zone, &local)?,
+            None => 0,
+        };
+
+        let time = local.to_system_time();
+        match offset < 0 {
+            true => time.checked_add(Duration::from_secs(offset.unsigned_abs())),
+            false => time.checked_sub(Duration::from_secs(offset as u64)),
+        }
+    })
+}
+
+// Seconds east of UTC of a time zone at a local time, "ET" and the like switching to daylight saving time
+fn zone_offset(zone: &str, local: &DateTime) -> Option<i64> {
+    let hours = match zone {
+        "UTC" | "GMT" | "WET" => 0,
+        "BST" | "WEST" | "CET" => 1,
+        "CEST" | "EET" => 2,
+        "EEST" | "MSK" => 3,
+        "EDT" => -4,
+        "EST" | "CDT" => -5,
+        "CST" | "MDT" => -6,
+        "MST" | "PDT" => -7,
+        "PST" => -8,
+        "ET" => -5 + us_daylight_saving(local) as i64,
+        "CT" => -6 + us_daylight_saving(local) as i64,
+        "MT" => -7 + us_daylight_saving(local) as i64,
+        "PT" => -8 + us_daylight_saving(local) as i64,
+        _ => return None,
+    };
+
+    Some(hours * 3600)
+}
+
+// From 2:00 on the second Sunday of March to 2:00 on the first Sunday of November
+fn us_daylight_saving(local: &DateTime) -> bool {
+    // January 1, 1970 was a Thursday
+    let sunday = |month: u32, week: u32| {
+        let first = DateTime { month, day: 1, hour: 0, minute: 0, second: 0, ..*local }.to_system_time();
+        let weekday = (first.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / 86_400 + 4) % 7;
+        1 + (7 - weekday as u32) % 7 + 7 * (week - 1)
+    };
+
+    let now = (local.month, local.day, local.hour);
+    (3, sunday(3, 2), 2) <= now && now < (11, sunday(11, 1), 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::tests::{as_written, played_hands};
+
+    // The uncalled bet is written right after the last bet, before the board is run out
+    fn uncalled_apart(mut history: HandHistory) -> (HandHistory, Vec<EventKind>) {
+        let (uncalled, events) = history.events.into_iter().partition(|event| matches!(event.kind, EventKind::Uncalled { .. }));
+        history.events = events;
+        (history, uncalled.into_iter().map(|event| event.kind).collect())
+    }
+
+    #[test]
+    fn written_hands_are_read_back() {
+        let histories = played_hands(1, 150);
+
+        let parsed = parse(&write_all(&histories)).unwrap();
+        assert_eq!(parsed.len(), histories.len());
+        for (history, parsed) in histories.iter().zip(parsed) {
+            assert_eq!(uncalled_apart(as_written(history)), uncalled_apart(parsed), "hand {}", history.number);
+        }
+    }
+
+    #[test]
+    fn early_shows_and_time_zones_are_read() {
+        let text = "\
+PokerStars Hand #230000000001: Hold'em No Limit ($0.05/$0.10 USD) - 2021/07/01 20:00:00 CEST [2021/07/01 14:00:00 ET]
+Table 'Alcyone' 6-max Seat #1 is the button
+Seat 1: Alice ($10 in chips)
+Seat 2: Bob ($8.50 in chips)
+Seat 3: Carol ($10 in chips)
+Bob: posts small blind $0.05
+Carol: posts big blind $0.10
+*** HOLE CARDS ***
+Alice: raises $9.90 to $10 and is all-in
+Bob: calls $8.45 and is all-in
+Carol: folds
+Uncalled bet ($1.50) returned to Alice
+Alice: shows [Ah Ad]
+Bob: shows [Kc Ks]
+*** FLOP *** [2c 7d 9h]
+*** TURN *** [2c 7d 9h] [Js]
+*** RIVER *** [2c 7d 9h Js] [3s]
+*** SHOW DOWN ***
+Alice collected $17.10 from pot
+*** SUMMARY ***
+Total pot $17.10 | Rake $0
+";
+        let history = parse(text).unwrap().remove(0);
+
+        assert_eq!(DateTime::from_system_time(history.started), DateTime::new(2021, 7, 1, 18, 0, 0).unwrap());
+        let shown: Vec<usize> = history.events.iter().filter_map(|event| match event.kind {
+            EventKind::Show { seat, .. } => Some(seat),
+            _ => None,
+        }).collect();
+        assert_eq!(shown, [0, 1]);
+        assert_eq!(history.collected(0), 1710 + 150);
+    }
+
+    #[test]
+    fn eastern_time_follows_daylight_saving() {
+        let started = |date: &str| {
+            let header = format!("PokerStars Hand #1: Hold'em No Limit (5/10) - {date}\n");
+            let text = header + "Table 'T' 6-max Seat #1 is the button\nSeat 1: A (100 in chips)\nSeat 2: B (100 in chips)\n";
+            DateTime::from_system_time(parse(&text).unwrap()[0].started).hour
+        };
+
+        assert_eq!(started("2021/01/15 12:00:00 ET"), 17);
+        assert_eq!(started("2021/03/14 03:00:00 ET"), 7);
+        assert_eq!(started("2021/11/07 02:30:00 ET"), 7);
+        assert!(parse("PokerStars Hand #1: Hold'em No Limit (5/10) - 2021/01/15 12:00:00 XYZ\n").is_err());
+    }
+}
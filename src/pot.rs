@@ -0,0 +1,252 @@
+// https://en.wikipedia.org/wiki/Betting_in_poker#Side_pots
+// https://en.wikipedia.org/wiki/Split_(poker)
+
+use std::fmt;
+
+use crate::game_logic::Strength;
+
+// Chips a seat put into the pot during a hand
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Contribution {
+    pub seat: usize,
+    pub amount: u32,
+    pub folded: bool,
+    // Part of the amount which is dead money, like a big blind ante or a dead small blind
+    pub dead: u32,
+}
+
+// The main pot or a side pot, with the seats which can win it
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+}
+
+/*
+    Split the contributions into the main pot and side pots. Every level at which a player
+    is all-in starts a new pot, which only the players who put in at least as much and did
+    not fold can win. Chips nobody else matched are returned as the uncalled bet. Dead money
+    is not matched by anyone and goes into the main pot.
+*/
+pub fn build_pots(contributions: &[Contribution]) -> (Vec<Pot>, Option<(usize, u32)>) {
+    let dead: u32 = contributions.iter().map(|contribution| contribution.dead).sum();
+    let contributions: Vec<Contribution> = contributions
+        .iter()
+        .map(|contribution| Contribution { amount: contribution.amount - contribution.dead, ..*contribution })
+        .collect();
+
+    let mut levels: Vec<u32> = contributions.iter().map(|contribution| contribution.amount).filter(|&amount| amount > 0).collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let in_hand: Vec<usize> = contributions.iter().filter(|contribution| !contribution.folded).map(|contribution| contribution.seat).collect();
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut uncalled = None;
+    let mut previous = 0;
+
+    for level in levels {
+        let contributors: Vec<&Contribution> = contributions.iter().filter(|contribution| contribution.amount >= level).collect();
+        let amount: u32 = contributions.iter().map(|contribution| contribution.amount.min(level).saturating_sub(previous)).sum();
+        let eligible: Vec<usize> = contributors.iter().filter(|contribution| !contribution.folded).map(|contribution| contribution.seat).collect();
+
+        previous = level;
+
+        match (&contributors[..], pots.last_mut()) {
+            // Only the player who bet the most put in this much
+            ([contributor], _) if !contributor.folded => uncalled = Some((contributor.seat, amount)),
+            // Layers with the same players make up one pot
+            (_, Some(last)) if last.eligible == eligible || eligible.is_empty() => last.amount += amount,
+            // Everyone who put in this much folded, so it goes to those still in the hand like dead money
+            _ if eligible.is_empty() => pots.push(Pot { amount, eligible: in_hand.clone() }),
+            _ => pots.push(Pot { amount, eligible }),
+        }
+    }
+
+    if dead > 0 {
+        match pots.first_mut() {
+            Some(main) => main.amount += dead,
+            None => pots.push(Pot { amount: dead, eligible: in_hand }),
+        }
+    }
+
+    (pots, uncalled)
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PotAward {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+    // Seats which won the pot, with the chips each received including odd chips
+    pub winners: Vec<(usize, u32)>,
+    // The winning hand, none if the pot was won without a showdown
+    pub strength: Option<Strength>,
+}
+
+// How the chips of a hand were distributed, for display and auditing
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PotBreakdown {
+    pub pots: Vec<PotAward>,
+    pub uncalled: Option<(usize, u32)>,
+}
+
+impl PotBreakdown {
+    pub fn total(&self) -> u32 {
+        self.pots.iter().map(|pot| pot.amount).sum::<u32>() + self.uncalled.map(|(_, amount)| amount).unwrap_or(0)
+    }
+
+    // Chips received per seat over all pots and the uncalled bet, in seat order
+    pub fn winnings(&self) -> Vec<(usize, u32)> {
+        let mut winnings: Vec<(usize, u32)> = Vec::new();
+
+        for (seat, amount) in self.pots.iter().flat_map(|pot| pot.winners.iter().copied()).chain(self.uncalled) {
+            match winnings.iter_mut().find(|(other, _)| *other == seat) {
+                Some((_, total)) => *total += amount,
+                None => winnings.push((seat, amount)),
+            }
+        }
+
+        winnings.sort_unstable();
+        winnings
+    }
+}
+
+impl fmt::Display for PotBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, pot) in self.pots.iter().enumerate() {
+            let name = match i {
+                0 => "Main pot".to_string(),
+                i => format!("Side pot {i}"),
+            };
+            let eligible: Vec<String> = pot.eligible.iter().map(|seat| seat.to_string()).collect();
+            let winners: Vec<String> = pot.winners.iter().map(|(seat, amount)| format!("seat {seat} wins {amount}")).collect();
+
+            write!(f, "{name} {} (seats {}): {}", pot.amount, eligible.join(", "), winners.join(", "))?;
+            if let Some(strength) = &pot.strength {
+                write!(f, " with {}", strength.ranking)?;
+            }
+            writeln!(f)?;
+        }
+
+        if let Some((seat, amount)) = self.uncalled {
+            writeln!(f, "Uncalled bet {amount} returned to seat {seat}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/*
+    Award every pot to the best hand among its eligible seats, given the strengths of the
+    hands shown down (a pot whose only eligible seat did not show is won without showdown).
+    Tied hands split the pot evenly, the odd chips go one each to the winners in clockwise
+    order starting with the first seat left of the button.
+*/
+pub fn award(contributions: &[Contribution], shown: &[(usize, Strength)], button: usize, seat_count: usize) -> PotBreakdown {
+    let (pots, uncalled) = build_pots(contributions);
+    let left_of_button = |seat: usize| (seat + seat_count - button - 1) % seat_count;
+
+    let pots = pots
+        .into_iter()
+        .map(|pot| {
+            let strength = shown.iter().filter(|(seat, _)| pot.eligible.contains(seat)).map(|(_, strength)| strength).max();
+
+            let mut winners: Vec<usize> = match strength {
+                Some(best) => shown.iter().filter(|(seat, strength)| pot.eligible.contains(seat) && strength == best).map(|(seat, _)| *seat).collect(),
+                None => pot.eligible.clone(),
+            };
+            winners.sort_by_key(|&seat| left_of_button(seat));
+
+            let share = pot.amount / winners.len() as u32;
+            let odd_chips = pot.amount as usize % winners.len();
+
+            PotAward {
+                amount: pot.amount,
+                winners: winners.iter().enumerate().map(|(i, &seat)| (seat, share + (i < odd_chips) as u32)).collect(),
+                eligible: pot.eligible,
+                strength: strength.cloned(),
+            }
+        })
+        .collect();
+
+    PotBreakdown { pots, uncalled }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::Card, equity::showdown_strength};
+
+    fn contribution(seat: usize, amount: u32, folded: bool) -> Contribution {
+        Contribution { seat, amount, folded, dead: 0 }
+    }
+
+    fn strength(hole_cards: &str, board: &str) -> Strength {
+        let cards = |text: &str| text.split_whitespace().map(|card| card.parse::<Card>().unwrap()).collect::<Vec<Card>>();
+        let hole_cards = cards(hole_cards);
+        showdown_strength((hole_cards[0], hole_cards[1]), &cards(board))
+    }
+
+    #[test]
+    fn all_ins_start_side_pots_without_folded_seats() {
+        let contributions = [contribution(0, 50, false), contribution(1, 100, true), contribution(2, 200, false), contribution(3, 200, false)];
+
+        let (pots, uncalled) = build_pots(&contributions);
+        assert_eq!(
+            pots,
+            [
+                Pot { amount: 200, eligible: vec![0, 2, 3] },
+                // The layers from 50 to 100 and to 200 have the same players
+                Pot { amount: 350, eligible: vec![2, 3] },
+            ]
+        );
+        assert_eq!(uncalled, None);
+    }
+
+    #[test]
+    fn unmatched_chips_are_returned() {
+        let (pots, uncalled) = build_pots(&[contribution(0, 100, false), contribution(1, 40, false), contribution(2, 10, true)]);
+
+        assert_eq!(pots, [Pot { amount: 90, eligible: vec![0, 1] }]);
+        assert_eq!(uncalled, Some((0, 60)));
+    }
+
+    #[test]
+    fn dead_money_goes_to_the_main_pot() {
+        let contributions = [
+            Contribution { seat: 0, amount: 10, folded: true, dead: 0 },
+            // A dead small blind from a player who did not put in anything else
+            Contribution { seat: 1, amount: 5, folded: false, dead: 5 },
+        ];
+
+        let (pots, uncalled) = build_pots(&contributions);
+        assert_eq!(pots, [Pot { amount: 15, eligible: vec![1] }]);
+        assert_eq!(uncalled, None);
+
+        let breakdown = award(&contributions, &[], 0, 2);
+        assert_eq!(breakdown.winnings(), [(1, 15)]);
+    }
+
+    #[test]
+    fn side_pots_go_to_the_best_hand_among_the_eligible_seats() {
+        // The short stack is all-in, the third player folded on the river after matching the second
+        let contributions = [contribution(0, 30, false), contribution(1, 100, false), contribution(2, 100, true)];
+        let board = "2c 7d 9h Js 3s";
+        let shown = [(0, strength("Ah Ad", board)), (1, strength("Kc Ks", board))];
+
+        let breakdown = award(&contributions, &shown, 2, 3);
+        assert_eq!(breakdown.pots.len(), 2);
+        assert_eq!(breakdown.pots[0].winners, [(0, 90)]);
+        assert_eq!(breakdown.pots[1].winners, [(1, 140)]);
+        assert_eq!(breakdown.total(), 230);
+    }
+
+    #[test]
+    fn odd_chips_go_left_of_the_button_first() {
+        let contributions = [contribution(0, 11, true), contribution(1, 30, false), contribution(2, 30, false), contribution(3, 30, false)];
+        let board = "Ac Kd Qh Js Ts";
+        let shown = [(1, strength("2c 3d", board)), (2, strength("2h 3h", board)), (3, strength("4c 4d", board))];
+
+        let breakdown = award(&contributions, &shown, 1, 4);
+        assert_eq!(breakdown.pots[0].winners, [(2, 34), (3, 34), (1, 33)]);
+    }
+}
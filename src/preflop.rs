@@ -0,0 +1,317 @@
+// https://en.wikipedia.org/wiki/Texas_hold_%27em_starting_hands
+// https://en.wikipedia.org/wiki/Poker_probability_(Texas_hold_%27em)#Head-to-head_probabilities
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use rand::{seq::SliceRandom, thread_rng};
+use rayon::prelude::*;
+
+use crate::{
+    canonical::{StartingHand, STARTING_HAND_COUNT},
+    equity::{self, remaining_cards, Method},
+    range::{Combo, HandRange},
+};
+
+pub const FORMAT_VERSION: u32 = 1;
+pub const MAX_OPPONENTS: usize = 9;
+
+// Number of table entries: all opponent counts per hand, plus each unordered pair of hands
+pub const ENTRY_COUNT: usize = STARTING_HAND_COUNT * MAX_OPPONENTS + STARTING_HAND_COUNT * (STARTING_HAND_COUNT + 1) / 2;
+
+#[derive(Debug)]
+pub enum PreflopTableError {
+    Io(io::Error),
+    Version(u32),
+    Parse(usize, String),
+    IterationMismatch { expected: u32, found: u32 },
+}
+
+impl fmt::Display for PreflopTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreflopTableError::Io(error) => write!(f, "{error}"),
+            PreflopTableError::Version(version) => {
+                write!(f, "unsupported table version {version}, expected {FORMAT_VERSION}")
+            }
+            PreflopTableError::Parse(line, message) => write!(f, "line {line}: {message}"),
+            PreflopTableError::IterationMismatch { expected, found } => {
+                write!(f, "table was generated with {found} iterations, expected {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreflopTableError {}
+
+impl From<io::Error> for PreflopTableError {
+    fn from(error: io::Error) -> Self {
+        PreflopTableError::Io(error)
+    }
+}
+
+/*
+    All-in equity (in percent) of every starting hand against 1 to 9 random opponents and
+    against every other starting hand heads-up. Entries are None until computed.
+
+    File format (text, one entry per line, '-' marks a missing value):
+
+        version 1
+        iterations 100000
+        vs_random AA 85.2035 73.4761 ...    (1 to 9 opponents)
+        heads_up AA KK 81.9470               (only for the first hand not after the second)
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct PreflopTable {
+    iterations: u32,
+    vs_random: Vec<[Option<f32>; MAX_OPPONENTS]>,
+    heads_up: Vec<Option<f32>>,
+}
+
+impl PreflopTable {
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations,
+            vs_random: vec![[None; MAX_OPPONENTS]; STARTING_HAND_COUNT],
+            heads_up: vec![None; STARTING_HAND_COUNT * STARTING_HAND_COUNT],
+        }
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub fn vs_random(&self, hand: StartingHand, opponents: usize) -> Option<f32> {
+        self.vs_random[hand.index()].get(opponents.checked_sub(1)?).copied().flatten()
+    }
+
+    pub fn heads_up(&self, hand: StartingHand, other: StartingHand) -> Option<f32> {
+        self.heads_up[hand.index() * STARTING_HAND_COUNT + other.index()]
+    }
+
+    pub fn completed(&self) -> usize {
+        let vs_random = self.vs_random.iter().flatten().filter(|entry| entry.is_some()).count();
+        let heads_up = (0..STARTING_HAND_COUNT)
+            .flat_map(|first| (first..STARTING_HAND_COUNT).map(move |second| (first, second)))
+            .filter(|&(first, second)| self.heads_up[first * STARTING_HAND_COUNT + second].is_some())
+            .count();
+
+        vs_random + heads_up
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed() == ENTRY_COUNT
+    }
+
+    fn set_vs_random(&mut self, hand: StartingHand, opponents: usize, equity: f32) {
+        self.vs_random[hand.index()][opponents - 1] = Some(equity);
+    }
+
+    // Heads-up equities of both hands add up to 100 %
+    fn set_heads_up(&mut self, hand: StartingHand, other: StartingHand, equity: f32) {
+        self.heads_up[hand.index() * STARTING_HAND_COUNT + other.index()] = Some(equity);
+        self.heads_up[other.index() * STARTING_HAND_COUNT + hand.index()] = Some(100. - equity);
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PreflopTableError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, PreflopTableError> {
+        let mut table: Option<PreflopTable> = None;
+        let mut version = None;
+
+        for (number, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| PreflopTableError::Parse(number, message.to_string());
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match (fields[0], version, table.as_mut()) {
+                ("version", None, _) => {
+                    let found = parse_field(&fields, 1).ok_or_else(|| error("invalid version"))?;
+                    if found != FORMAT_VERSION {
+                        return Err(PreflopTableError::Version(found));
+                    }
+                    version = Some(found);
+                }
+                (_, None, _) => return Err(error("expected version first")),
+                ("iterations", Some(_), None) => {
+                    let iterations = parse_field(&fields, 1).ok_or_else(|| error("invalid iterations"))?;
+                    table = Some(PreflopTable::new(iterations));
+                }
+                (_, Some(_), None) => return Err(error("expected iterations after version")),
+                ("vs_random", Some(_), Some(table)) if fields.len() == 2 + MAX_OPPONENTS => {
+                    let hand = parse_field(&fields, 1).ok_or_else(|| error("invalid starting hand"))?;
+
+                    for opponents in 1..=MAX_OPPONENTS {
+                        match fields[1 + opponents] {
+                            "-" => {}
+                            value => {
+                                let equity = value.parse().map_err(|_| error("invalid equity"))?;
+                                table.set_vs_random(hand, opponents, equity);
+                            }
+                        }
+                    }
+                }
+                ("heads_up", Some(_), Some(table)) if fields.len() == 4 => {
+                    let hand = parse_field(&fields, 1).ok_or_else(|| error("invalid starting hand"))?;
+                    let other = parse_field(&fields, 2).ok_or_else(|| error("invalid starting hand"))?;
+                    let equity = parse_field(&fields, 3).ok_or_else(|| error("invalid equity"))?;
+
+                    table.set_heads_up(hand, other, equity);
+                }
+                _ => return Err(error("unknown entry")),
+            }
+        }
+
+        table.ok_or_else(|| PreflopTableError::Parse(0, "missing header".to_string()))
+    }
+
+    // Written to a temporary file first, so an interrupted save never corrupts the table
+    pub fn save(&self, path: &Path) -> Result<(), PreflopTableError> {
+        let temporary = path.with_extension("tmp");
+
+        let mut file = io::BufWriter::new(fs::File::create(&temporary)?);
+        file.write_all(self.to_string().as_bytes())?;
+        file.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+
+        fs::rename(&temporary, path)?;
+
+        Ok(())
+    }
+
+    /*
+        Compute all missing entries with the given number of Monte Carlo iterations each,
+        saving after every starting hand. An existing file at the path is loaded first, so
+        an interrupted generation resumes where it stopped. The progress callback receives
+        the number of completed and total entries.
+    */
+    pub fn generate(path: &Path, iterations: u32, mut progress: impl FnMut(usize, usize)) -> Result<Self, PreflopTableError> {
+        let mut table = match path.exists() {
+            true => Self::load(path)?,
+            false => Self::new(iterations),
+        };
+
+        if table.iterations != iterations {
+            return Err(PreflopTableError::IterationMismatch { expected: iterations, found: table.iterations });
+        }
+
+        let hands = StartingHand::all();
+        let mut completed = table.completed();
+        progress(completed, ENTRY_COUNT);
+
+        for (i, &hand) in hands.iter().enumerate() {
+            let mut changed = false;
+
+            for opponents in 1..=MAX_OPPONENTS {
+                if table.vs_random(hand, opponents).is_none() {
+                    table.set_vs_random(hand, opponents, vs_random_equity(hand, opponents, iterations) as f32);
+                    changed = true;
+                    completed += 1;
+                    progress(completed, ENTRY_COUNT);
+                }
+            }
+
+            for &other in &hands[i..] {
+                if table.heads_up(hand, other).is_none() {
+                    table.set_heads_up(hand, other, heads_up_equity(hand, other, iterations) as f32);
+                    changed = true;
+                    completed += 1;
+                    progress(completed, ENTRY_COUNT);
+                }
+            }
+
+            if changed {
+                table.save(path)?;
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+impl fmt::Display for PreflopTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |equity: Option<f32>| equity.map(|equity| format!("{equity:.4}")).unwrap_or("-".to_string());
+
+        writeln!(f, "# rust-texas-holdem preflop equity table")?;
+        writeln!(f, "version {FORMAT_VERSION}")?;
+        writeln!(f, "iterations {}", self.iterations)?;
+
+        let hands = StartingHand::all();
+
+        for &hand in &hands {
+            let values: Vec<String> = (1..=MAX_OPPONENTS).map(|opponents| value(self.vs_random(hand, opponents))).collect();
+            writeln!(f, "vs_random {hand} {}", values.join(" "))?;
+        }
+
+        for (i, &hand) in hands.iter().enumerate() {
+            for &other in &hands[i..] {
+                if let Some(equity) = self.heads_up(hand, other) {
+                    writeln!(f, "heads_up {hand} {other} {}", value(Some(equity)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Equity against random hands is the same for every combo of a class, so only its representative is dealt
+pub fn vs_random_equity(hand: StartingHand, opponents: usize, iterations: u32) -> f64 {
+    let hole_cards = hand.representative();
+    let remaining = remaining_cards(&[hole_cards.0, hole_cards.1]);
+
+    let share: f64 = (0..iterations)
+        .into_par_iter()
+        .fold(
+            || (0., remaining.clone(), Vec::with_capacity(opponents + 1)),
+            |(share, mut cards, mut players): (f64, _, Vec<Combo>), _| {
+                let (drawn, _) = cards.partial_shuffle(&mut thread_rng(), opponents * 2 + 5);
+
+                players.clear();
+                players.push(hole_cards);
+                players.extend(drawn[5..].chunks(2).map(|pair| (pair[0], pair[1])));
+
+                let (winners, _) = equity::winners(&players, &drawn[..5]);
+                let share = match winners.contains(&0) {
+                    true => share + 1. / winners.len() as f64,
+                    false => share,
+                };
+
+                (share, cards, players)
+            },
+        )
+        .map(|(share, _, _)| share)
+        .sum();
+
+    share / iterations as f64 * 100.
+}
+
+pub fn heads_up_equity(hand: StartingHand, other: StartingHand, iterations: u32) -> f64 {
+    if hand == other {
+        return 50.;
+    }
+
+    let range = |hand: StartingHand| {
+        let mut range = HandRange::new();
+        for combo in hand.combos() {
+            range.add(combo, 1.);
+        }
+        range
+    };
+
+    equity::calculate_ranges(&[range(hand), range(other)], &[], &[], Method::MonteCarlo { iterations })
+        .map(|result| result.equity_percentage(0))
+        .unwrap_or(50.)
+}
+
+fn parse_field<T: std::str::FromStr>(fields: &[&str], index: usize) -> Option<T> {
+    fields.get(index)?.parse().ok()
+}
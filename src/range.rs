@@ -0,0 +1,451 @@
+// https://en.wikipedia.org/wiki/Texas_hold_%27em_starting_hands
+// https://www.pokerstrategy.com/strategy/various-poker/texas-holdem-starting-hands-notation/
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+use crate::card::{Card, Face, Suit};
+
+pub const COMBO_COUNT: usize = 1_326;           // (52 !) / ((50 !) * (2 !))
+
+// Two hole cards, higher card first
+pub type Combo = (Card, Card);
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+enum Kind {
+    Pair,
+    Suited,
+    Offsuit,
+    Any,
+}
+
+impl Kind {
+    fn abbr(&self) -> &str {
+        match self {
+            Kind::Pair | Kind::Any => "",
+            Kind::Suited => "s",
+            Kind::Offsuit => "o",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+struct Class {
+    high: Face,
+    low: Face,
+    kind: Kind,
+}
+
+impl Class {
+    fn combos(&self) -> Vec<Combo> {
+        let mut combos = Vec::new();
+
+        for high_suit in Suit::ALL {
+            for low_suit in Suit::ALL {
+                let matches = match self.kind {
+                    Kind::Pair => (high_suit as u8) > (low_suit as u8),
+                    Kind::Suited => high_suit == low_suit,
+                    Kind::Offsuit => high_suit != low_suit,
+                    Kind::Any => true,
+                };
+
+                if matches {
+                    combos.push(normalize((
+                        Card { face: self.high, suit: high_suit },
+                        Card { face: self.low, suit: low_suit },
+                    )));
+                }
+            }
+        }
+
+        combos
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParseRangeError {
+    InvalidToken(String),
+    InvalidWeight(String),
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRangeError::InvalidToken(token) => write!(f, "invalid range token '{token}'"),
+            ParseRangeError::InvalidWeight(weight) => write!(f, "invalid weight '{weight}', expected a number in (0, 1]"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+// Weighted set of hole card combos, keyed by card indices so iteration order is stable
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct HandRange {
+    combos: BTreeMap<(usize, usize), f32>,
+}
+
+impl HandRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /*
+        Notation           Combos
+        ------------------------------------------------------
+        random / any       All 1326 combos
+        QQ                 Pair of queens
+        QQ+                Queens or better
+        22-66              Pairs from deuces to sixes
+        AKs / AKo / AK     Suited, offsuit or both
+        AJo+               AJo, AQo, AKo (kicker up to one below the top card)
+        A2s-A5s            A2s, A3s, A4s, A5s
+        76s-54s            76s, 65s, 54s (top and kicker move together)
+        AsKh               Specific combo
+        AKo:0.5            Any of the above with a weight in (0, 1]
+    */
+    pub fn parse(notation: &str) -> Result<Self, ParseRangeError> {
+        let mut range = Self::new();
+
+        for token in notation.split(',').map(str::trim).filter(|token| !token.is_empty()) {
+            let (spec, weight) = match token.split_once(':') {
+                Some((spec, weight)) => (spec.trim(), parse_weight(weight.trim())?),
+                None => (token, 1.0),
+            };
+
+            for combo in expand(spec).ok_or_else(|| ParseRangeError::InvalidToken(token.to_string()))? {
+                range.add(combo, weight);
+            }
+        }
+
+        Ok(range)
+    }
+
+    // Adding a combo that is already present replaces its weight
+    pub fn add(&mut self, combo: Combo, weight: f32) {
+        let (high, low) = normalize(combo);
+        self.combos.insert((high.index(), low.index()), weight);
+    }
+
+    pub fn remove_dead(&mut self, dead_cards: &[Card]) {
+        let dead: Vec<usize> = dead_cards.iter().map(Card::index).collect();
+        self.combos.retain(|(high, low), _| !dead.contains(high) && !dead.contains(low));
+    }
+
+    pub fn contains(&self, combo: &Combo) -> bool {
+        self.weight(combo).is_some()
+    }
+
+    pub fn weight(&self, combo: &Combo) -> Option<f32> {
+        let (high, low) = normalize(*combo);
+        self.combos.get(&(high.index(), low.index())).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    pub fn combos(&self) -> impl Iterator<Item = (Combo, f32)> + '_ {
+        self.combos
+            .iter()
+            .map(|(&(high, low), &weight)| ((Card::from_index(high), Card::from_index(low)), weight))
+    }
+
+    // Weight of a whole class if all of its combos are present with the same weight
+    fn class_weight(&self, class: &Class) -> Option<f32> {
+        let mut weights = class.combos().into_iter().map(|combo| self.weight(&combo));
+        let first = weights.next()??;
+
+        weights.all(|weight| weight == Some(first)).then_some(first)
+    }
+
+    fn notation(&self) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut covered: BTreeSet<(usize, usize)> = BTreeSet::new();
+        let mut singles: Vec<(Class, f32)> = Vec::new();
+
+        // Pairs, in runs from the top
+        let pairs: Vec<(Face, Option<f32>)> = Face::ALL
+            .into_iter()
+            .map(|face| (face, self.class_weight(&Class { high: face, low: face, kind: Kind::Pair })))
+            .collect();
+
+        for run in runs(&pairs) {
+            let (top, bottom, weight) = run;
+
+            for face in Face::ALL.into_iter().filter(|&face| face <= top && face >= bottom) {
+                cover(&mut covered, &Class { high: face, low: face, kind: Kind::Pair });
+            }
+
+            let notation = if top == bottom {
+                format!("{top}{top}")
+            } else if top == Face::Ace {
+                format!("{bottom}{bottom}+")
+            } else {
+                format!("{top}{top}-{bottom}{bottom}")
+            };
+
+            tokens.push(with_weight(notation, weight));
+        }
+
+        // Non-pairs, in kicker runs per top card; suited and offsuit merge when both are complete
+        for high in Face::ALL {
+            let kickers: Vec<Face> = Face::ALL.into_iter().filter(|&face| face < high).collect();
+
+            for kind in [Kind::Any, Kind::Suited, Kind::Offsuit] {
+                let slots: Vec<(Face, Option<f32>)> = kickers
+                    .iter()
+                    .map(|&low| {
+                        let suited = self.class_weight(&Class { high, low, kind: Kind::Suited });
+                        let offsuit = self.class_weight(&Class { high, low, kind: Kind::Offsuit });
+                        let merged = suited.filter(|_| suited == offsuit);
+
+                        let weight = match kind {
+                            Kind::Any => merged,
+                            Kind::Suited => suited.filter(|_| merged.is_none()),
+                            Kind::Offsuit => offsuit.filter(|_| merged.is_none()),
+                            Kind::Pair => unreachable!(),
+                        };
+
+                        (low, weight)
+                    })
+                    .collect();
+
+                for (top, bottom, weight) in runs(&slots) {
+                    for low in kickers.iter().copied().filter(|&face| face <= top && face >= bottom) {
+                        cover(&mut covered, &Class { high, low, kind });
+                    }
+
+                    if top == bottom {
+                        singles.push((Class { high, low: top, kind }, weight));
+                        continue;
+                    }
+
+                    let kind = kind.abbr();
+                    let notation = if kickers.first() == Some(&top) {
+                        format!("{high}{bottom}{kind}+")
+                    } else {
+                        format!("{high}{top}{kind}-{high}{bottom}{kind}")
+                    };
+
+                    tokens.push(with_weight(notation, weight));
+                }
+            }
+        }
+
+        // Single classes chain diagonally when top card and kicker move together, e.g. "76s-54s"
+        let below = |(class, weight): &(Class, f32)| -> Option<(Class, f32)> {
+            let next = Class { high: offset(class.high, 1)?, low: offset(class.low, 1)?, ..*class };
+            singles.iter().find(|(other, other_weight)| *other == next && other_weight == weight).copied()
+        };
+
+        for single in &singles {
+            let is_chained = singles.iter().any(|above| below(above) == Some(*single));
+            if is_chained {
+                continue;
+            }
+
+            let (top, weight) = *single;
+            let mut bottom = top;
+            while let Some((next, _)) = below(&(bottom, weight)) {
+                bottom = next;
+            }
+
+            let kind = top.kind.abbr();
+            let notation = if top == bottom {
+                format!("{}{}{kind}", top.high, top.low)
+            } else {
+                format!("{}{}{kind}-{}{}{kind}", top.high, top.low, bottom.high, bottom.low)
+            };
+
+            tokens.push(with_weight(notation, weight));
+        }
+
+        // Remaining combos of incomplete classes
+        for (&(high, low), &weight) in self.combos.iter().rev() {
+            if !covered.contains(&(high, low)) {
+                tokens.push(with_weight(format!("{}{}", Card::from_index(high), Card::from_index(low)), weight));
+            }
+        }
+
+        tokens
+    }
+}
+
+impl FromStr for HandRange {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+// Shortest notation of the combo set, e.g. "QQ+, AJs+, KQo"
+impl fmt::Display for HandRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(weight) = self.combos.values().next().filter(|_| self.len() == COMBO_COUNT) {
+            if self.combos.values().all(|other| other == weight) {
+                return write!(f, "{}", with_weight("random".to_string(), *weight));
+            }
+        }
+
+        write!(f, "{}", self.notation().join(", "))
+    }
+}
+
+fn cover(covered: &mut BTreeSet<(usize, usize)>, class: &Class) {
+    covered.extend(class.combos().iter().map(|(high, low)| (high.index(), low.index())));
+}
+
+fn normalize(combo: Combo) -> Combo {
+    if combo.0.index() >= combo.1.index() {
+        combo
+    } else {
+        (combo.1, combo.0)
+    }
+}
+
+fn parse_weight(weight: &str) -> Result<f32, ParseRangeError> {
+    match weight.parse::<f32>() {
+        Ok(value) if value > 0.0 && value <= 1.0 => Ok(value),
+        _ => Err(ParseRangeError::InvalidWeight(weight.to_string())),
+    }
+}
+
+fn with_weight(notation: String, weight: f32) -> String {
+    if weight == 1.0 {
+        notation
+    } else {
+        format!("{notation}:{weight}")
+    }
+}
+
+// Collapse consecutive slots (ordered high to low) with equal weight into (top, bottom, weight)
+fn runs(slots: &[(Face, Option<f32>)]) -> Vec<(Face, Face, f32)> {
+    let mut runs: Vec<(Face, Face, f32)> = Vec::new();
+    let mut previous: Option<f32> = None;
+
+    for &(face, weight) in slots {
+        match (weight, runs.last_mut()) {
+            (Some(weight), Some(run)) if previous == Some(weight) => run.1 = face,
+            (Some(weight), _) => runs.push((face, face, weight)),
+            (None, _) => {}
+        }
+
+        previous = weight;
+    }
+
+    runs
+}
+
+fn parse_class(spec: &str) -> Option<Class> {
+    let mut chars = spec.chars();
+
+    let first = Face::from_symbol(chars.next()?)?;
+    let second = Face::from_symbol(chars.next()?)?;
+    let suffix = chars.next();
+
+    if chars.next().is_some() {
+        return None;
+    }
+
+    let (high, low) = if first >= second { (first, second) } else { (second, first) };
+
+    let kind = match (high == low, suffix.map(|c| c.to_ascii_lowercase())) {
+        (true, None) => Kind::Pair,
+        (true, Some(_)) => return None,
+        (false, None) => Kind::Any,
+        (false, Some('s')) => Kind::Suited,
+        (false, Some('o')) => Kind::Offsuit,
+        (false, Some(_)) => return None,
+    };
+
+    Some(Class { high, low, kind })
+}
+
+fn faces_between(from: Face, to: Face) -> Vec<Face> {
+    let (bottom, top) = if from <= to { (from, to) } else { (to, from) };
+
+    Face::ALL.into_iter().filter(|&face| face >= bottom && face <= top).collect()
+}
+
+fn offset(face: Face, by: u8) -> Option<Face> {
+    let value = (face as u8).checked_sub(by)?;
+    Face::ALL.into_iter().find(|face| *face as u8 == value)
+}
+
+fn expand(spec: &str) -> Option<Vec<Combo>> {
+    if spec.eq_ignore_ascii_case("random") || spec.eq_ignore_ascii_case("any") {
+        return Some(expand_classes(
+            Face::ALL.into_iter().flat_map(|high| {
+                Face::ALL.into_iter().filter(move |&low| low <= high).map(move |low| Class {
+                    high,
+                    low,
+                    kind: if high == low { Kind::Pair } else { Kind::Any },
+                })
+            }),
+        ));
+    }
+
+    // Specific combo such as "AsKh"
+    if spec.len() == 4 && spec.is_ascii() {
+        if let (Ok(first), Ok(second)) = (spec[0..2].parse::<Card>(), spec[2..4].parse::<Card>()) {
+            return (!first.is_same(&second)).then(|| vec![normalize((first, second))]);
+        }
+    }
+
+    if let Some(spec) = spec.strip_suffix('+') {
+        let class = parse_class(spec)?;
+
+        return Some(match class.kind {
+            Kind::Pair => expand_classes(
+                faces_between(class.high, Face::Ace).into_iter().map(|face| Class { high: face, low: face, ..class }),
+            ),
+            _ => expand_classes(
+                faces_between(class.low, offset(class.high, 1)?).into_iter().map(|low| Class { low, ..class }),
+            ),
+        });
+    }
+
+    if let Some((from, to)) = spec.split_once('-') {
+        let from = parse_class(from)?;
+        let to = parse_class(to)?;
+
+        if from.kind != to.kind {
+            return None;
+        }
+
+        return Some(match from.kind {
+            Kind::Pair => {
+                expand_classes(faces_between(from.high, to.high).into_iter().map(|face| Class { high: face, low: face, ..from }))
+            }
+            _ if from.high == to.high => {
+                expand_classes(faces_between(from.low, to.low).into_iter().map(|low| Class { low, ..from }))
+            }
+            _ if from.high as u8 - from.low as u8 == to.high as u8 - to.low as u8 => {
+                let gap = from.high as u8 - from.low as u8;
+
+                expand_classes(
+                    faces_between(from.high, to.high)
+                        .into_iter()
+                        .map(|high| Some(Class { high, low: offset(high, gap)?, ..from }))
+                        .collect::<Option<Vec<_>>>()?,
+                )
+            }
+            _ => return None,
+        });
+    }
+
+    parse_class(spec).map(|class| class.combos())
+}
+
+fn expand_classes(classes: impl IntoIterator<Item = Class>) -> Vec<Combo> {
+    classes.into_iter().flat_map(|class| class.combos()).collect()
+}
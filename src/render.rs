@@ -0,0 +1,224 @@
+// https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+// https://en.wikipedia.org/wiki/Box-drawing_characters
+// https://en.wikipedia.org/wiki/Four-color_deck
+
+use crate::card::{Card, Suit};
+
+const CARD_WIDTH: usize = 7;
+const BOARD_SLOTS: usize = 5;
+const SEATS_PER_ROW: usize = 4;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_BLUE: &str = "\x1b[34m";
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ColorScheme {
+    Monochrome,
+    TwoColor,
+    FourColor,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Charset {
+    Ascii,
+    Unicode,
+}
+
+#[derive(Debug)]
+pub struct SeatView<'a> {
+    pub name: &'a str,
+    pub stack: u32,
+    pub hole_cards: Option<(Card, Card)>,
+    pub face_up: bool,
+    pub is_button: bool,
+}
+
+#[derive(Debug)]
+pub struct TableView<'a> {
+    pub seats: Vec<SeatView<'a>>,
+    pub board: &'a [Card],
+    pub pot: u32,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Renderer {
+    colors: ColorScheme,
+    charset: Charset,
+}
+
+impl Renderer {
+    pub fn new(colors: ColorScheme, charset: Charset) -> Self {
+        Self { colors, charset }
+    }
+
+    pub fn card(&self, card: &Card) -> String {
+        self.card_lines(card).join("\n")
+    }
+
+    pub fn card_back(&self) -> String {
+        self.card_back_lines().join("\n")
+    }
+
+    pub fn hand(&self, cards: &[Card]) -> String {
+        let blocks: Vec<Vec<String>> = cards.iter().map(|card| self.card_lines(card)).collect();
+
+        join_blocks(&blocks, " ").join("\n")
+    }
+
+    // Undealt board cards are drawn as empty slots
+    pub fn board(&self, board: &[Card]) -> String {
+        self.board_lines(board).join("\n")
+    }
+
+    pub fn table(&self, table: &TableView) -> String {
+        let mut lines = Vec::new();
+
+        lines.push(format!("Board (pot {})", table.pot));
+        lines.extend(self.board_lines(table.board));
+
+        for row in table.seats.chunks(SEATS_PER_ROW) {
+            lines.push(String::new());
+
+            let blocks: Vec<Vec<String>> = row.iter().map(|seat| self.seat_lines(seat)).collect();
+            lines.extend(join_blocks(&blocks, "   "));
+        }
+
+        lines.join("\n")
+    }
+
+    fn card_lines(&self, card: &Card) -> Vec<String> {
+        let face = card.face.symbol();
+        let suit = match self.charset {
+            Charset::Ascii => card.suit.abbr().chars().next().unwrap(),
+            Charset::Unicode => card.suit.symbol(),
+        };
+
+        let color = self.suit_color(card.suit);
+
+        self.frame([format!("{face}    "), format!("  {suit}  "), format!("    {face}")])
+            .into_iter()
+            .map(|line| paint(&line, color))
+            .collect()
+    }
+
+    fn card_back_lines(&self) -> Vec<String> {
+        let pattern = match self.charset {
+            Charset::Ascii => "#####",
+            Charset::Unicode => "░░░░░",
+        };
+
+        let color = match self.colors {
+            ColorScheme::Monochrome => None,
+            ColorScheme::TwoColor | ColorScheme::FourColor => Some(ANSI_BLUE),
+        };
+
+        self.frame([pattern.to_string(), pattern.to_string(), pattern.to_string()])
+            .into_iter()
+            .map(|line| paint(&line, color))
+            .collect()
+    }
+
+    fn empty_slot_lines(&self) -> Vec<String> {
+        let (top, side, bottom) = match self.charset {
+            Charset::Ascii => (".....", ':', "....."),
+            Charset::Unicode => ("┄┄┄┄┄", '┆', "┄┄┄┄┄"),
+        };
+        let (top_left, top_right, bottom_left, bottom_right) = match self.charset {
+            Charset::Ascii => ('.', '.', '.', '.'),
+            Charset::Unicode => ('┌', '┐', '└', '┘'),
+        };
+
+        let mut lines = vec![format!("{top_left}{top}{top_right}")];
+        lines.extend((0..3).map(|_| format!("{side}     {side}")));
+        lines.push(format!("{bottom_left}{bottom}{bottom_right}"));
+
+        lines
+    }
+
+    fn board_lines(&self, board: &[Card]) -> Vec<String> {
+        let blocks: Vec<Vec<String>> = (0..BOARD_SLOTS)
+            .map(|slot| match board.get(slot) {
+                Some(card) => self.card_lines(card),
+                None => self.empty_slot_lines(),
+            })
+            .collect();
+
+        join_blocks(&blocks, " ")
+    }
+
+    fn seat_lines(&self, seat: &SeatView) -> Vec<String> {
+        let width = CARD_WIDTH * 2 + 1;
+
+        let button = if seat.is_button { " (D)" } else { "" };
+        let label: String = format!("{}{button}", seat.name).chars().take(width).collect();
+        let stack: String = format!("{}", seat.stack).chars().take(width).collect();
+
+        let cards = match seat.hole_cards {
+            Some((first, second)) if seat.face_up => join_blocks(&[self.card_lines(&first), self.card_lines(&second)], " "),
+            Some(_) => join_blocks(&[self.card_back_lines(), self.card_back_lines()], " "),
+            None => vec![" ".repeat(width); 5],
+        };
+
+        let mut lines = vec![format!("{label:<width$}"), format!("{stack:<width$}")];
+        lines.extend(cards);
+
+        lines
+    }
+
+    fn frame(&self, inner: [String; 3]) -> Vec<String> {
+        let (horizontal, vertical, top_left, top_right, bottom_left, bottom_right) = match self.charset {
+            Charset::Ascii => ('-', '|', '+', '+', '+', '+'),
+            Charset::Unicode => ('─', '│', '┌', '┐', '└', '┘'),
+        };
+        let border: String = std::iter::repeat_n(horizontal, CARD_WIDTH - 2).collect();
+
+        let mut lines = vec![format!("{top_left}{border}{top_right}")];
+        lines.extend(inner.iter().map(|line| format!("{vertical}{line}{vertical}")));
+        lines.push(format!("{bottom_left}{border}{bottom_right}"));
+
+        lines
+    }
+
+    #[rustfmt::skip]
+    fn suit_color(&self, suit: Suit) -> Option<&'static str> {
+        match (self.colors, suit) {
+            (ColorScheme::Monochrome, _)              => None,
+            (_, Suit::Spades)                         => None,
+            (_, Suit::Hearts)                         => Some(ANSI_RED),
+            (ColorScheme::TwoColor, Suit::Diamonds)   => Some(ANSI_RED),
+            (ColorScheme::TwoColor, Suit::Clubs)      => None,
+            (ColorScheme::FourColor, Suit::Diamonds)  => Some(ANSI_BLUE),
+            (ColorScheme::FourColor, Suit::Clubs)     => Some(ANSI_GREEN),
+        }
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new(ColorScheme::TwoColor, Charset::Unicode)
+    }
+}
+
+fn paint(line: &str, color: Option<&str>) -> String {
+    match color {
+        Some(color) => format!("{color}{line}{ANSI_RESET}"),
+        None => line.to_string(),
+    }
+}
+
+// Place multi-line blocks of equal height side by side
+fn join_blocks(blocks: &[Vec<String>], separator: &str) -> Vec<String> {
+    let height = blocks.iter().map(|block| block.len()).max().unwrap_or(0);
+
+    (0..height)
+        .map(|row| {
+            blocks
+                .iter()
+                .map(|block| block.get(row).map(|line| line.as_str()).unwrap_or(""))
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+        .collect()
+}
@@ -0,0 +1,381 @@
+// https://en.wikipedia.org/wiki/Hand_history
+// https://en.wikipedia.org/wiki/Betting_in_poker
+
+use std::fmt;
+
+use crate::{
+    betting::{BettingContext, BettingStructure},
+    blinds::Ante,
+    card::Card,
+    equity::showdown_strength,
+    game_logic::Strength,
+    history::{EventKind, HandHistory, Post},
+    pot::{self, Contribution},
+    range::Combo,
+    simulation::Street,
+    table::Action,
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ReplayError {
+    DuplicateCard(Card),
+    Board { street: Street, cards: usize },
+    IllegalAction(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::DuplicateCard(card) => write!(f, "card {card} is dealt more than once"),
+            ReplayError::Board { street, cards } => write!(f, "board has {cards} cards on the {street:?}, expected {}", street.board_cards()),
+            ReplayError::IllegalAction(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/*
+    Replays a hand read from a hand history format into its history, checking that it could
+    have been played: no card is dealt twice, the board grows by the right number of cards,
+    players only act while in the hand, and bets, raises and calls are of a size the betting
+    structure allows with the chips they have left. Whether players act in turn is not
+    checked, as formats do not always record every action.
+*/
+#[derive(Debug)]
+pub struct Replay {
+    history: HandHistory,
+    structure: Box<dyn BettingStructure>,
+    stacks: Vec<u32>,
+    bets: Vec<u32>,
+    folded: Vec<bool>,
+    street: Street,
+    board: Vec<Card>,
+    seen: Vec<Card>,
+    current_bet: u32,
+    min_raise: u32,
+    raises: u32,
+}
+
+impl Replay {
+    // Starts from the players' stacks before posting, with no events recorded yet
+    pub fn new(history: HandHistory, structure: Box<dyn BettingStructure>) -> Self {
+        let mut stacks = vec![0; history.seat_count];
+        for record in &history.seats {
+            stacks[record.seat] = record.stack;
+        }
+
+        let big_blind = history.blinds.big_blind;
+
+        Self {
+            bets: vec![0; history.seat_count],
+            folded: vec![false; history.seat_count],
+            min_raise: structure.opening_size(Street::Preflop, big_blind),
+            history,
+            structure,
+            stacks,
+            street: Street::Preflop,
+            board: Vec::new(),
+            seen: Vec::new(),
+            current_bet: big_blind,
+            raises: 1,
+        }
+    }
+
+    pub fn history(&self) -> &HandHistory {
+        &self.history
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    // The seat's bet on the street and the chips behind
+    pub fn bet(&self, seat: usize) -> u32 {
+        self.bets[seat]
+    }
+
+    pub fn stack(&self, seat: usize) -> u32 {
+        self.stacks[seat]
+    }
+
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    pub fn is_posted(&self, blind: Post) -> bool {
+        self.history.events.iter().any(|event| matches!(event.kind, EventKind::Post { post, .. } if post == blind))
+    }
+
+    pub fn post(&mut self, seat: usize, post: Post, amount: u32) -> Result<(), ReplayError> {
+        if amount > self.stacks[seat] {
+            return Err(ReplayError::IllegalAction(format!("post of {amount} with {} behind", self.stacks[seat])));
+        }
+
+        self.stacks[seat] -= amount;
+        if !matches!(post, Post::Ante | Post::MissedSmallBlind) {
+            self.bets[seat] += amount;
+            self.current_bet = self.current_bet.max(self.bets[seat]);
+        }
+
+        /*
+            The straddle is a blind raise, setting the size of the next one. A player all-in
+            for less posted the usual straddle of twice the big blind, which is still the bet
+            to call, as formats do not give its size.
+        */
+        if post == Post::Straddle {
+            let straddle = match self.stacks[seat] {
+                0 => amount.max(self.history.blinds.big_blind * 2),
+                _ => amount,
+            };
+            self.current_bet = self.current_bet.max(straddle);
+            self.min_raise = self.structure.opening_size(Street::Preflop, straddle);
+            self.raises = 2;
+        }
+
+        if amount > 0 {
+            self.history.record(EventKind::Post { seat, post, amount });
+        }
+
+        Ok(())
+    }
+
+    pub fn deal(&mut self, seat: usize, cards: Combo) -> Result<(), ReplayError> {
+        self.see(&[cards.0, cards.1])?;
+        self.history.record(EventKind::Dealt { seat, cards });
+
+        Ok(())
+    }
+
+    // Deals the cards added to the board for the street, which starts a new round of betting
+    pub fn deal_board(&mut self, street: Street, cards: Vec<Card>) -> Result<(), ReplayError> {
+        if street <= self.street || self.board.len() + cards.len() != street.board_cards() {
+            return Err(ReplayError::Board { street, cards: self.board.len() + cards.len() });
+        }
+
+        self.see(&cards)?;
+        self.board.extend(&cards);
+        self.history.record(EventKind::Board { street, cards });
+
+        self.street = street;
+        self.bets.iter_mut().for_each(|bet| *bet = 0);
+        self.current_bet = 0;
+        self.min_raise = self.structure.opening_size(street, self.history.blinds.big_blind);
+        self.raises = 0;
+
+        Ok(())
+    }
+
+    // Takes the action for the seat, returning the chips put in
+    pub fn act(&mut self, seat: usize, action: Action) -> Result<u32, ReplayError> {
+        let illegal = |reason: String| Err(ReplayError::IllegalAction(reason));
+
+        if self.folded[seat] {
+            return illegal("player has folded".to_string());
+        }
+        if self.stacks[seat] == 0 {
+            return illegal("player is all-in".to_string());
+        }
+
+        let to_call = self.current_bet.saturating_sub(self.bets[seat]);
+
+        let put_in = match action {
+            Action::Fold => {
+                self.folded[seat] = true;
+                0
+            }
+            Action::Check if to_call > 0 => return illegal(format!("check facing a bet of {to_call}")),
+            Action::Check => 0,
+            Action::Call if to_call == 0 => return illegal("call without a bet to call".to_string()),
+            Action::Call => to_call.min(self.stacks[seat]),
+            Action::Bet(_) if self.current_bet > 0 => return illegal("bet facing a bet, expected a raise".to_string()),
+            Action::Raise(_) if self.current_bet == 0 => return illegal("raise without a bet, expected a bet".to_string()),
+            Action::Bet(total) | Action::Raise(total) => {
+                let context = BettingContext {
+                    street: self.street,
+                    big_blind: self.history.blinds.big_blind,
+                    current_bet: self.current_bet,
+                    min_raise: self.min_raise,
+                    raises: self.raises,
+                    pot: self.history.seats.iter().map(|record| self.history.invested(record.seat)).sum(),
+                    bet: self.bets[seat],
+                    stack: self.stacks[seat],
+                    players: self.history.seats.iter().filter(|record| !self.folded[record.seat]).count(),
+                };
+
+                if total > context.all_in() {
+                    return illegal(format!("{action} with {} behind", self.stacks[seat]));
+                }
+                match self.structure.sizes(&context) {
+                    Some((min, max)) if (min..=max).contains(&total) => {}
+                    Some((min, max)) => return illegal(format!("{action}, allowed {min} to {max}")),
+                    None => return illegal(format!("{action} not allowed")),
+                }
+
+                // Only a full bet or raise sets the minimum for the next one
+                if total - self.current_bet >= self.min_raise {
+                    self.min_raise = total - self.current_bet;
+                    self.raises += 1;
+                }
+                self.current_bet = total;
+
+                total - self.bets[seat]
+            }
+        };
+
+        self.stacks[seat] -= put_in;
+        self.bets[seat] += put_in;
+
+        let all_in = put_in > 0 && self.stacks[seat] == 0;
+        self.history.record(EventKind::Action { street: self.street, seat, action, amount: put_in, all_in });
+
+        Ok(put_in)
+    }
+
+    // Shows the seat's hand, which must be the cards dealt if they are known
+    pub fn show(&mut self, seat: usize, cards: Combo) -> Result<(), ReplayError> {
+        match self.history.hole_cards(seat) {
+            Some(dealt) if !same_cards(dealt, cards) => return Err(ReplayError::IllegalAction("shown cards are not the dealt cards".to_string())),
+            Some(_) => {}
+            None => self.see(&[cards.0, cards.1])?,
+        }
+
+        // Only hands shown down with the whole board have a strength
+        if self.board.len() == 5 {
+            let strength = showdown_strength(cards, &self.board);
+            self.history.record(EventKind::Show { seat, cards, strength });
+        }
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, kind: EventKind) {
+        self.history.record(kind);
+    }
+
+    // Returns the uncalled bet, for formats which give the pots, and the chips left in the pots
+    pub fn return_uncalled(&mut self) -> u32 {
+        let contributions = self.contributions();
+
+        let (_, uncalled) = pot::build_pots(&contributions);
+        if let Some((seat, amount)) = uncalled {
+            self.history.record(EventKind::Uncalled { seat, amount });
+        }
+
+        contributions.iter().map(|contribution| contribution.amount).sum::<u32>() - uncalled.map(|(_, amount)| amount).unwrap_or(0)
+    }
+
+    /*
+        Shows down and awards the pots, for formats which do not give them. Hands not shown are
+        taken from the dealt cards, players whose cards are unknown mucked. Nothing is awarded
+        while the hand is not over, or when nobody at the showdown has known cards.
+    */
+    pub fn award(&mut self) {
+        let in_hand: Vec<usize> = self.history.seats.iter().map(|record| record.seat).filter(|&seat| !self.folded[seat]).collect();
+        let showdown = in_hand.len() > 1;
+
+        if showdown && self.board.len() < 5 {
+            return;
+        }
+
+        let mut shown: Vec<(usize, Strength)> = Vec::new();
+        for &seat in in_hand.iter().filter(|_| showdown) {
+            let strength = self.history.events.iter().find_map(|event| match &event.kind {
+                EventKind::Show { seat: shower, strength, .. } if *shower == seat => Some(strength.clone()),
+                _ => None,
+            });
+
+            match (strength, self.history.hole_cards(seat)) {
+                (Some(strength), _) => shown.push((seat, strength)),
+                (None, Some(cards)) => {
+                    let strength = showdown_strength(cards, &self.board);
+                    self.history.record(EventKind::Show { seat, cards, strength: strength.clone() });
+                    shown.push((seat, strength));
+                }
+                (None, None) => {}
+            }
+        }
+
+        if showdown && shown.is_empty() {
+            return;
+        }
+
+        let mut contributions = self.contributions();
+        for contribution in contributions.iter_mut() {
+            contribution.folded |= showdown && !shown.iter().any(|(seat, _)| *seat == contribution.seat);
+        }
+
+        let pots = pot::award(&contributions, &shown, self.history.button, self.history.seat_count);
+        if let Some((seat, amount)) = pots.uncalled {
+            self.history.record(EventKind::Uncalled { seat, amount });
+        }
+        for (pot, award) in pots.pots.iter().enumerate() {
+            for &(seat, amount) in &award.winners {
+                self.history.record(EventKind::Win { seat, pot, amount });
+            }
+        }
+    }
+
+    // The history with the blinds as posted, every event at the time the hand started
+    pub fn finish(mut self) -> HandHistory {
+        self.history.blinds = self.history.posted_blinds();
+
+        let started = self.history.started;
+        for event in self.history.events.iter_mut() {
+            event.time = started;
+        }
+
+        self.history
+    }
+
+    // Big blind antes and dead small blinds are dead money, as at the table
+    fn contributions(&self) -> Vec<Contribution> {
+        let big_blind_ante = matches!(self.history.posted_blinds().ante, Ante::BigBlind(_));
+
+        self.history
+            .seats
+            .iter()
+            .map(|record| Contribution {
+                seat: record.seat,
+                amount: self.history.invested(record.seat),
+                folded: self.folded[record.seat],
+                dead: self
+                    .history
+                    .events
+                    .iter()
+                    .map(|event| match event.kind {
+                        EventKind::Post { seat, post: Post::MissedSmallBlind, amount } if seat == record.seat => amount,
+                        EventKind::Post { seat, post: Post::Ante, amount } if seat == record.seat && big_blind_ante => amount,
+                        _ => 0,
+                    })
+                    .sum(),
+            })
+            .collect()
+    }
+
+    // Cards must be unique across the hole cards and the board
+    fn see(&mut self, cards: &[Card]) -> Result<(), ReplayError> {
+        for card in cards {
+            if self.seen.iter().any(|seen| seen.is_same(card)) {
+                return Err(ReplayError::DuplicateCard(*card));
+            }
+            self.seen.push(*card);
+        }
+
+        Ok(())
+    }
+}
+
+// An amount of chips, or of money in cents with a scale of 100
+pub fn chips(amount: f64, scale: f64) -> Option<u32> {
+    let chips = (amount * scale).round();
+    (chips.is_finite() && chips >= 0. && chips <= u32::MAX as f64).then_some(chips as u32)
+}
+
+fn same_cards(a: Combo, b: Combo) -> bool {
+    (a.0.is_same(&b.0) && a.1.is_same(&b.1)) || (a.0.is_same(&b.1) && a.1.is_same(&b.0))
+}
@@ -0,0 +1,529 @@
+// https://en.wikipedia.org/wiki/Poker_probability#7-card_poker_hands
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{
+    canonical::{StartingHand, STARTING_HAND_COUNT},
+    card::Card,
+    deck::Deck,
+    equity,
+    evaluator::{self, CardSet, HandRank},
+    game_logic::Ranking,
+    range::Combo,
+    statistics::{self, ChiSquare, ConfidenceInterval, StopCondition},
+};
+
+// Iterations are dealt in chunks, each with its own random number generator
+pub const CHUNK_SIZE: u32 = 10_000;
+
+// Throughput a release build is expected to reach per worker thread at the river
+pub const TARGET_HANDS_PER_SECOND_PER_THREAD: f64 = 500_000.;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    pub fn board_cards(&self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub iterations: u32,
+    // Defaults to rayon's global thread pool
+    pub threads: Option<usize>,
+    // Makes results reproducible, independent of the number of threads
+    pub seed: Option<u64>,
+    pub street: Street,
+    // Hands dealt and evaluated per iteration, sharing the board
+    pub players: usize,
+    pub dead_cards: Vec<Card>,
+    // Stop before all iterations once the largest standard error of the frequencies is reached
+    pub stop: StopCondition,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 1_000_000,
+            threads: None,
+            seed: None,
+            street: Street::River,
+            players: 1,
+            dead_cards: Vec::new(),
+            stop: StopCondition::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SimulationError {
+    Players(usize),
+    ShowdownPlayers(usize),
+    DuplicateDeadCard(Card),
+    ThreadPool(rayon::ThreadPoolBuildError),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::Players(players) => write!(f, "cannot deal {players} players from the remaining deck"),
+            SimulationError::ShowdownPlayers(players) => write!(f, "showdowns of {players} players are not supported, at most {}", equity::MAX_PLAYERS),
+            SimulationError::DuplicateDeadCard(card) => write!(f, "dead card {card} is given more than once"),
+            SimulationError::ThreadPool(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+#[derive(Clone, Debug)]
+pub struct RankingCheck {
+    pub ranking: Ranking,
+    pub observed: f64,
+    pub expected: f64,
+    pub interval: ConfidenceInterval,
+}
+
+impl RankingCheck {
+    pub fn is_within(&self) -> bool {
+        self.interval.contains(self.expected)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Validation {
+    pub rankings: Vec<RankingCheck>,
+    pub chi_square: ChiSquare,
+    pub significance: f64,
+}
+
+impl Validation {
+    // A failed test points at a broken evaluator or a biased shuffle
+    pub fn passed(&self) -> bool {
+        self.chi_square.p_value >= self.significance
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationResult {
+    pub counts: HashMap<Ranking, u64>,
+    pub hands: u64,
+    pub elapsed: Duration,
+    pub threads: usize,
+    pub iterations: u64,
+}
+
+impl SimulationResult {
+    pub fn count(&self, ranking: Ranking) -> u64 {
+        self.counts.get(&ranking).copied().unwrap_or(0)
+    }
+
+    pub fn probability(&self, ranking: Ranking) -> f64 {
+        match self.hands {
+            0 => 0.,
+            hands => self.count(ranking) as f64 / hands as f64,
+        }
+    }
+
+    // Largest standard error of the ranking frequencies
+    pub fn standard_error(&self) -> f64 {
+        Ranking::ALL
+            .into_iter()
+            .map(|ranking| statistics::proportion_standard_error(self.probability(ranking), self.hands))
+            .fold(0., f64::max)
+    }
+
+    pub fn hands_per_second(&self) -> f64 {
+        self.hands as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn micros_per_hand(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1_000_000. / self.hands as f64
+    }
+
+    pub fn target_hands_per_second(&self) -> f64 {
+        TARGET_HANDS_PER_SECOND_PER_THREAD * self.threads as f64
+    }
+
+    pub fn meets_throughput_target(&self) -> bool {
+        self.hands_per_second() >= self.target_hands_per_second()
+    }
+
+    /*
+        Compare the observed frequencies with Ranking::probability(), which holds for 7-card
+        hands, so only single-player river simulations are expected to pass. Each ranking gets
+        a confidence interval (e.g. 0.99), the chi-square test fails below the significance level.
+    */
+    pub fn validate(&self, confidence: f64, significance: f64) -> Validation {
+        let rankings = Ranking::ALL
+            .into_iter()
+            .map(|ranking| RankingCheck {
+                ranking,
+                observed: self.probability(ranking),
+                expected: ranking.probability() as f64,
+                interval: statistics::confidence_interval(self.count(ranking), self.hands, confidence),
+            })
+            .collect();
+
+        let observed: Vec<u64> = Ranking::ALL.into_iter().map(|ranking| self.count(ranking)).collect();
+        let probabilities: Vec<f64> = Ranking::ALL.into_iter().map(|ranking| ranking.probability() as f64).collect();
+
+        Validation {
+            rankings,
+            chi_square: statistics::chi_square(&observed, &probabilities),
+            significance,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StartingHandStats {
+    pub dealt: u64,
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    // Rankings of the outright wins, indexed by ranking
+    pub win_rankings: [u64; Ranking::ALL.len()],
+}
+
+impl StartingHandStats {
+    pub fn win_rate(&self) -> f64 {
+        self.rate(self.wins)
+    }
+
+    pub fn tie_rate(&self) -> f64 {
+        self.rate(self.ties)
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        self.rate(self.losses)
+    }
+
+    pub fn wins_with(&self, ranking: Ranking) -> u64 {
+        self.win_rankings[ranking as usize]
+    }
+
+    fn rate(&self, count: u64) -> f64 {
+        match self.dealt {
+            0 => 0.,
+            dealt => count as f64 / dealt as f64,
+        }
+    }
+
+    fn merge(&mut self, other: &StartingHandStats) {
+        self.dealt += other.dealt;
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+
+        for (count, other) in self.win_rankings.iter_mut().zip(other.win_rankings) {
+            *count += other;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShowdownResult {
+    pub players: usize,
+    // Indexed by StartingHand::index()
+    pub hands: Vec<StartingHandStats>,
+    pub elapsed: Duration,
+    pub threads: usize,
+    pub iterations: u64,
+}
+
+impl ShowdownResult {
+    pub fn stats(&self, hand: StartingHand) -> &StartingHandStats {
+        &self.hands[hand.index()]
+    }
+
+    // Largest standard error of the win rates of the starting hands
+    pub fn standard_error(&self) -> f64 {
+        self.hands
+            .iter()
+            .map(|stats| statistics::proportion_standard_error(stats.win_rate(), stats.dealt))
+            .fold(0., f64::max)
+    }
+
+    // Starting hands ordered from the highest win rate to the lowest
+    pub fn ranked(&self) -> Vec<(StartingHand, &StartingHandStats)> {
+        let mut ranked: Vec<_> = self.hands.iter().enumerate().map(|(i, stats)| (StartingHand::from_index(i), stats)).collect();
+        ranked.sort_by(|(_, a), (_, b)| b.win_rate().total_cmp(&a.win_rate()));
+        ranked
+    }
+}
+
+/*
+    Deal the configured number of hands per iteration from shuffled decks and count their
+    rankings at the given street. The progress callback receives the number of completed
+    and total iterations after every chunk.
+*/
+pub fn simulate(config: &SimulationConfig, progress: impl Fn(u64, u64) + Sync) -> Result<SimulationResult, SimulationError> {
+    for (i, card) in config.dead_cards.iter().enumerate() {
+        if config.dead_cards[..i].iter().any(|other| other.is_same(card)) {
+            return Err(SimulationError::DuplicateDeadCard(*card));
+        }
+    }
+
+    if config.players == 0 || config.players * 2 + config.street.board_cards() + config.dead_cards.len() > 52 {
+        return Err(SimulationError::Players(config.players));
+    }
+
+    match config.threads {
+        Some(threads) => rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(SimulationError::ThreadPool)?
+            .install(|| Ok(run(config, &progress))),
+        None => Ok(run(config, &progress)),
+    }
+}
+
+/*
+    Deal full tables of the configured number of players, run every hand to the river and
+    record for each starting hand how often it wins outright, ties or loses at showdown.
+    The configured street is ignored, the stop condition applies to the win rates. At most
+    equity::MAX_PLAYERS players are supported.
+*/
+pub fn simulate_showdowns(config: &SimulationConfig, progress: impl Fn(u64, u64) + Sync) -> Result<ShowdownResult, SimulationError> {
+    for (i, card) in config.dead_cards.iter().enumerate() {
+        if config.dead_cards[..i].iter().any(|other| other.is_same(card)) {
+            return Err(SimulationError::DuplicateDeadCard(*card));
+        }
+    }
+
+    if config.players < 2 || config.players * 2 + Street::River.board_cards() + config.dead_cards.len() > 52 {
+        return Err(SimulationError::Players(config.players));
+    }
+
+    if config.players > equity::MAX_PLAYERS {
+        return Err(SimulationError::ShowdownPlayers(config.players));
+    }
+
+    match config.threads {
+        Some(threads) => rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(SimulationError::ThreadPool)?
+            .install(|| Ok(run_showdowns(config, &progress))),
+        None => Ok(run_showdowns(config, &progress)),
+    }
+}
+
+fn run(config: &SimulationConfig, progress: &(impl Fn(u64, u64) + Sync)) -> SimulationResult {
+    let mut result = SimulationResult {
+        counts: HashMap::new(),
+        hands: 0,
+        elapsed: Duration::ZERO,
+        threads: rayon::current_num_threads(),
+        iterations: 0,
+    };
+
+    run_batches(
+        config,
+        progress,
+        || [0u64; Ranking::ALL.len()],
+        |chunk| run_chunk(config, chunk),
+        merge_counts,
+        |counts, iterations, elapsed| {
+            result.counts = Ranking::ALL.into_iter().zip(*counts).collect();
+            result.hands = counts.iter().sum();
+            result.iterations = iterations;
+            result.elapsed = elapsed;
+
+            config.stop.is_met(result.standard_error(), elapsed)
+        },
+    );
+
+    result
+}
+
+/*
+    Every chunk counts into its own accumulator, which are merged by rayon's reduce, so the
+    workers never synchronize. With a stop condition, chunks run in batches and the update
+    callback receives the totals after each one, returning whether to stop.
+*/
+fn run_batches<T: Send>(
+    config: &SimulationConfig,
+    progress: &(impl Fn(u64, u64) + Sync),
+    empty: impl Fn() -> T + Sync + Send,
+    run_chunk: impl Fn(u32) -> T + Sync + Send,
+    merge: impl Fn(T, T) -> T + Sync + Send,
+    mut update: impl FnMut(&T, u64, Duration) -> bool,
+) {
+    let completed = AtomicU64::new(0);
+
+    let start_time = Instant::now();
+
+    let chunks = config.iterations.div_ceil(CHUNK_SIZE);
+    let batch_size = match config.stop.is_adaptive() {
+        true => rayon::current_num_threads() as u32 * 4,
+        false => chunks,
+    };
+
+    let mut total = empty();
+    let mut batch_start = 0;
+
+    while batch_start < chunks {
+        let batch_end = (batch_start + batch_size).min(chunks);
+
+        let batch = (batch_start..batch_end)
+            .into_par_iter()
+            .map(|chunk| {
+                let accumulator = run_chunk(chunk);
+
+                let iterations = CHUNK_SIZE.min(config.iterations - chunk * CHUNK_SIZE) as u64;
+                let completed = completed.fetch_add(iterations, Ordering::Relaxed) + iterations;
+                progress(completed, config.iterations as u64);
+
+                accumulator
+            })
+            .reduce(&empty, &merge);
+
+        total = merge(total, batch);
+        batch_start = batch_end;
+
+        if update(&total, completed.load(Ordering::Relaxed), start_time.elapsed()) {
+            break;
+        }
+    }
+}
+
+/*
+    A chunk reuses one deck and only randomizes the cards it deals. Its hands are collected
+    as card sets and evaluated in one batch, so nothing is allocated per hand.
+*/
+fn run_chunk(config: &SimulationConfig, chunk: u32) -> [u64; Ranking::ALL.len()] {
+    let mut counts = [0u64; Ranking::ALL.len()];
+
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed ^ chunk as u64),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut deck = Deck::new();
+    deck.remove(&config.dead_cards);
+
+    let dealt = config.players * 2 + config.street.board_cards();
+    let iterations = CHUNK_SIZE.min(config.iterations - chunk * CHUNK_SIZE);
+
+    let mut hands = Vec::with_capacity(iterations as usize * config.players);
+
+    for _ in 0..iterations {
+        let cards = deck.deal_random(&mut rng, dealt);
+        let (board, hole_cards) = cards.split_at(config.street.board_cards());
+        let board = CardSet::from_cards(board);
+
+        hands.extend(hole_cards.chunks(2).map(|hole_cards| board.with(hole_cards[0]).with(hole_cards[1])));
+    }
+
+    let mut ranks = vec![HandRank::default(); hands.len()];
+    evaluator::evaluate_batch(&hands, &mut ranks);
+
+    for rank in ranks {
+        counts[rank.ranking() as usize] += 1;
+    }
+
+    counts
+}
+
+fn run_showdowns(config: &SimulationConfig, progress: &(impl Fn(u64, u64) + Sync)) -> ShowdownResult {
+    let mut result = ShowdownResult {
+        players: config.players,
+        hands: vec![StartingHandStats::default(); STARTING_HAND_COUNT],
+        elapsed: Duration::ZERO,
+        threads: rayon::current_num_threads(),
+        iterations: 0,
+    };
+
+    run_batches(
+        config,
+        progress,
+        || vec![StartingHandStats::default(); STARTING_HAND_COUNT],
+        |chunk| run_showdown_chunk(config, chunk),
+        |mut hands, other| {
+            for (stats, other) in hands.iter_mut().zip(&other) {
+                stats.merge(other);
+            }
+            hands
+        },
+        |hands, iterations, elapsed| {
+            result.hands.clone_from(hands);
+            result.iterations = iterations;
+            result.elapsed = elapsed;
+
+            config.stop.is_met(result.standard_error(), elapsed)
+        },
+    );
+
+    result
+}
+
+fn run_showdown_chunk(config: &SimulationConfig, chunk: u32) -> Vec<StartingHandStats> {
+    let mut hands = vec![StartingHandStats::default(); STARTING_HAND_COUNT];
+
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed ^ chunk as u64),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut deck = Deck::new();
+    deck.remove(&config.dead_cards);
+
+    let mut players: Vec<Combo> = Vec::with_capacity(config.players);
+    let iterations = CHUNK_SIZE.min(config.iterations - chunk * CHUNK_SIZE);
+
+    for _ in 0..iterations {
+        let cards = deck.deal_random(&mut rng, config.players * 2 + Street::River.board_cards());
+        let (board, hole_cards) = cards.split_at(Street::River.board_cards());
+
+        players.clear();
+        players.extend(hole_cards.chunks(2).map(|pair| (pair[0], pair[1])));
+
+        let (winners, ranking) = equity::winners(&players, board);
+
+        for (i, &hole_cards) in players.iter().enumerate() {
+            let stats = &mut hands[StartingHand::new(hole_cards).index()];
+            stats.dealt += 1;
+
+            match (winners.contains(&i), winners.len()) {
+                (true, 1) => {
+                    stats.wins += 1;
+                    stats.win_rankings[ranking as usize] += 1;
+                }
+                (true, _) => stats.ties += 1,
+                (false, _) => stats.losses += 1,
+            }
+        }
+    }
+
+    hands
+}
+
+fn merge_counts(mut counts: [u64; Ranking::ALL.len()], other: [u64; Ranking::ALL.len()]) -> [u64; Ranking::ALL.len()] {
+    for (count, other) in counts.iter_mut().zip(other) {
+        *count += other;
+    }
+
+    counts
+}
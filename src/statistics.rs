@@ -0,0 +1,247 @@
+// https://en.wikipedia.org/wiki/Binomial_proportion_confidence_interval#Wilson_score_interval
+// https://en.wikipedia.org/wiki/Pearson%27s_chi-squared_test
+// https://en.wikipedia.org/wiki/Incomplete_gamma_function#Regularized_gamma_functions_and_Poisson_random_variables
+// https://web.archive.org/web/20151030215612/http://home.online.no/~pjacklam/notes/invnorm/
+
+use std::time::Duration;
+
+// Categories with fewer expected observations are pooled for the chi-square test
+pub const MIN_EXPECTED_COUNT: f64 = 5.;
+
+// When an adaptive Monte Carlo run may stop early; with neither set it runs to its maximum
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub struct StopCondition {
+    pub target_standard_error: Option<f64>,
+    pub time_budget: Option<Duration>,
+}
+
+impl StopCondition {
+    pub fn is_met(&self, standard_error: f64, elapsed: Duration) -> bool {
+        self.target_standard_error.map(|target| standard_error <= target).unwrap_or(false)
+            || self.time_budget.map(|budget| elapsed >= budget).unwrap_or(false)
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        self.target_standard_error.is_some() || self.time_budget.is_some()
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn contains(&self, value: f64) -> bool {
+        value >= self.lower && value <= self.upper
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ChiSquare {
+    pub statistic: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+// Wilson score interval of a proportion observed successes out of trials, at the given confidence (e.g. 0.99)
+pub fn confidence_interval(successes: u64, trials: u64, confidence: f64) -> ConfidenceInterval {
+    if trials == 0 {
+        return ConfidenceInterval { lower: 0., upper: 1. };
+    }
+
+    let z = normal_quantile(1. - (1. - confidence) / 2.);
+    let n = trials as f64;
+    let p = successes as f64 / n;
+
+    let denominator = 1. + z * z / n;
+    let center = (p + z * z / (2. * n)) / denominator;
+    let half_width = z / denominator * (p * (1. - p) / n + z * z / (4. * n * n)).sqrt();
+
+    ConfidenceInterval {
+        lower: (center - half_width).max(0.),
+        upper: (center + half_width).min(1.),
+    }
+}
+
+/*
+    Pearson's goodness-of-fit test of observed counts against expected probabilities.
+    Trailing categories with too few expected observations are pooled into one.
+*/
+pub fn chi_square(observed: &[u64], probabilities: &[f64]) -> ChiSquare {
+    let total: u64 = observed.iter().sum();
+
+    let mut categories: Vec<(f64, f64)> = observed
+        .iter()
+        .zip(probabilities)
+        .map(|(&observed, &probability)| (observed as f64, probability * total as f64))
+        .collect();
+
+    while categories.len() > 1 && categories.last().unwrap().1 < MIN_EXPECTED_COUNT {
+        let (observed, expected) = categories.pop().unwrap();
+        let last = categories.last_mut().unwrap();
+        last.0 += observed;
+        last.1 += expected;
+    }
+
+    let statistic = categories
+        .iter()
+        .filter(|(_, expected)| *expected > 0.)
+        .map(|(observed, expected)| (observed - expected).powi(2) / expected)
+        .sum();
+    let degrees_of_freedom = categories.len().saturating_sub(1);
+
+    ChiSquare {
+        statistic,
+        degrees_of_freedom,
+        p_value: chi_square_p_value(statistic, degrees_of_freedom),
+    }
+}
+
+// Standard error of a proportion estimated from the given number of trials
+pub fn proportion_standard_error(proportion: f64, trials: u64) -> f64 {
+    match trials {
+        0 => f64::INFINITY,
+        trials => (proportion * (1. - proportion) / trials as f64).sqrt(),
+    }
+}
+
+// Standard error of a mean, given the sum and the sum of squares of the samples
+pub fn mean_standard_error(sum: f64, sum_of_squares: f64, samples: f64) -> f64 {
+    if samples < 2. {
+        return f64::INFINITY;
+    }
+
+    let mean = sum / samples;
+    let variance = (sum_of_squares / samples - mean * mean).max(0.) * samples / (samples - 1.);
+
+    (variance / samples).sqrt()
+}
+
+// Probability of a chi-square statistic at least this large, if the expected distribution is right
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    if degrees_of_freedom == 0 {
+        return 1.;
+    }
+
+    regularized_gamma_q(degrees_of_freedom as f64 / 2., statistic / 2.)
+}
+
+// Inverse of the standard normal distribution function (Acklam's rational approximation)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02, 1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02, 6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00, -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.754408661907416e+00];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+
+    if p <= 0. {
+        f64::NEG_INFINITY
+    } else if p >= 1. {
+        f64::INFINITY
+    } else if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    }
+}
+
+// Upper regularized incomplete gamma function Q(a, x), by series or continued fraction
+fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-14;
+    const MAX_ITERATIONS: usize = 1_000;
+
+    if x <= 0. {
+        return 1.;
+    }
+
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1. {
+        let mut term = 1. / a;
+        let mut sum = term;
+
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+
+        (1. - sum * prefactor).max(0.)
+    } else {
+        // Modified Lentz's method
+        let tiny = f64::MIN_POSITIVE / EPSILON;
+        let mut b = x + 1. - a;
+        let mut c = 1. / tiny;
+        let mut d = 1. / b;
+        let mut fraction = d;
+
+        for n in 1..MAX_ITERATIONS {
+            let an = -(n as f64) * (n as f64 - a);
+            b += 2.;
+
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+
+            d = 1. / d;
+            let delta = d * c;
+            fraction *= delta;
+
+            if (delta - 1.).abs() < EPSILON {
+                break;
+            }
+        }
+
+        (fraction * prefactor).min(1.)
+    }
+}
+
+// Lanczos approximation (g = 7, n = 9)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1. - x);
+    }
+
+    let x = x - 1.;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, coefficient)| sum + coefficient / (x + i as f64 + 1.));
+
+    0.5 * (2. * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
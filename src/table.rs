@@ -0,0 +1,901 @@
+// https://en.wikipedia.org/wiki/Texas_hold_%27em#Rules
+// https://en.wikipedia.org/wiki/Betting_in_poker
+
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{
+    betting::{BettingContext, BettingStructure, NoLimit},
+    blinds::{self, Ante, Blinds, MissedBlinds, Positions},
+    card::Card,
+    deck::Deck,
+    equity::showdown_strength,
+    game_logic::Strength,
+    history::{EventKind, HandHistory, Post, SeatRecord},
+    pot::{self, Contribution, Pot, PotBreakdown},
+    range::Combo,
+    render::{SeatView, TableView},
+    simulation::Street,
+};
+
+pub const MIN_SEATS: usize = 2;
+pub const MAX_SEATS: usize = 10;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum GameError {
+    SeatCount(usize),
+    InvalidSeat(usize),
+    SeatTaken(usize),
+    SeatEmpty(usize),
+    NotEnoughPlayers,
+    HandInProgress,
+    NoHandInProgress,
+    IllegalAction(Action),
+    StackOverflow { seat: usize, amount: u32 },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::SeatCount(count) => write!(f, "table has {count} seats, expected {MIN_SEATS} to {MAX_SEATS}"),
+            GameError::InvalidSeat(seat) => write!(f, "seat {seat} does not exist"),
+            GameError::SeatTaken(seat) => write!(f, "seat {seat} is already taken"),
+            GameError::SeatEmpty(seat) => write!(f, "seat {seat} is empty"),
+            GameError::NotEnoughPlayers => write!(f, "at least two players with chips are needed to start a hand"),
+            GameError::HandInProgress => write!(f, "a hand is in progress"),
+            GameError::NoHandInProgress => write!(f, "no hand is in progress"),
+            GameError::IllegalAction(action) => write!(f, "{action} is not a legal action"),
+            GameError::StackOverflow { seat, amount } => write!(f, "adding {amount} chips to seat {seat} overflows its stack"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+// Bet and raise amounts are the total the player has put in on the street afterwards
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u32),
+    Raise(u32),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Check => write!(f, "check"),
+            Action::Call => write!(f, "call"),
+            Action::Bet(amount) => write!(f, "bet {amount}"),
+            Action::Raise(amount) => write!(f, "raise to {amount}"),
+        }
+    }
+}
+
+// What the player to act may do; bets and raises may be of any total in the inclusive range
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LegalActions {
+    pub seat: usize,
+    pub check: bool,
+    // Chips needed to call, less than the difference if calling puts the player all-in
+    pub call: Option<u32>,
+    pub bet: Option<(u32, u32)>,
+    pub raise: Option<(u32, u32)>,
+}
+
+impl LegalActions {
+    pub fn allows(&self, action: Action) -> bool {
+        let within = |range: Option<(u32, u32)>, amount| range.map(|(min, max)| amount >= min && amount <= max).unwrap_or(false);
+
+        match action {
+            Action::Fold => true,
+            Action::Check => self.check,
+            Action::Call => self.call.is_some(),
+            Action::Bet(amount) => within(self.bet, amount),
+            Action::Raise(amount) => within(self.raise, amount),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Player {
+    pub name: String,
+}
+
+impl Player {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Seat {
+    pub player: Player,
+    pub stack: u32,
+    pub hole_cards: Option<Combo>,
+    // Chips put in on the current street and during the whole hand
+    pub bet: u32,
+    pub contributed: u32,
+    pub folded: bool,
+    // Not dealt in until sitting in again, the blinds passing meanwhile are owed
+    pub sitting_out: bool,
+    pub missed_blinds: MissedBlinds,
+    // Whether the player acted on this street, and the highest bet at that time
+    acted: bool,
+    faced: u32,
+    // Part of the contribution which is dead money
+    dead: u32,
+}
+
+impl Seat {
+    fn new(player: Player, stack: u32) -> Self {
+        Self {
+            player,
+            stack,
+            hole_cards: None,
+            bet: 0,
+            contributed: 0,
+            folded: false,
+            sitting_out: false,
+            missed_blinds: MissedBlinds::default(),
+            acted: false,
+            faced: 0,
+            dead: 0,
+        }
+    }
+
+    // Dealt into the current hand and not folded
+    pub fn is_in_hand(&self) -> bool {
+        self.hole_cards.is_some() && !self.folded
+    }
+
+    pub fn is_all_in(&self) -> bool {
+        self.is_in_hand() && self.stack == 0
+    }
+
+    fn can_act(&self) -> bool {
+        self.is_in_hand() && self.stack > 0
+    }
+
+    // Returns the chips actually put in, less than the amount if the player is all-in
+    fn put_in(&mut self, amount: u32) -> u32 {
+        let amount = amount.min(self.stack);
+        self.stack -= amount;
+        self.bet += amount;
+        self.contributed += amount;
+        amount
+    }
+
+    // Antes go into the pot without counting towards the bet on the street
+    fn post_ante(&mut self, amount: u32) -> u32 {
+        let amount = amount.min(self.stack);
+        self.stack -= amount;
+        self.contributed += amount;
+        amount
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Phase {
+    Waiting,
+    Betting(Street),
+    Complete,
+}
+
+#[derive(Clone, Debug)]
+pub struct HandResult {
+    pub board: Vec<Card>,
+    // Strength of every hand shown down, by seat; empty if everyone else folded
+    pub shown: Vec<(usize, Strength)>,
+    // Chips each seat received from the pot, including uncalled bets returned
+    pub winnings: Vec<(usize, u32)>,
+    pub pots: PotBreakdown,
+}
+
+/*
+    A table with a fixed number of seats, which runs one hand of Hold'em at a time: blinds
+    are posted, hole cards dealt from a shuffled deck and four betting rounds played, until
+    all but one player folded or the remaining hands are shown down.
+*/
+#[derive(Debug)]
+pub struct Table {
+    seats: Vec<Option<Seat>>,
+    // Takes effect from the next hand
+    pub blinds: Blinds,
+    positions: Option<Positions>,
+    deck: Deck,
+    board: Vec<Card>,
+    phase: Phase,
+    pub structure: Box<dyn BettingStructure>,
+    to_act: Option<usize>,
+    current_bet: u32,
+    min_raise: u32,
+    raises: u32,
+    result: Option<HandResult>,
+    hands: u64,
+    history: Option<HandHistory>,
+}
+
+impl Table {
+    pub fn new(seat_count: usize, blinds: Blinds) -> Result<Self, GameError> {
+        if !(MIN_SEATS..=MAX_SEATS).contains(&seat_count) {
+            return Err(GameError::SeatCount(seat_count));
+        }
+
+        Ok(Self {
+            seats: vec![None; seat_count],
+            blinds,
+            positions: None,
+            deck: Deck::new(),
+            board: Vec::new(),
+            phase: Phase::Waiting,
+            structure: Box::new(NoLimit),
+            to_act: None,
+            current_bet: 0,
+            min_raise: 0,
+            raises: 0,
+            result: None,
+            hands: 0,
+            history: None,
+        })
+    }
+
+    pub fn seat_count(&self) -> usize {
+        self.seats.len()
+    }
+
+    pub fn seat(&self, seat: usize) -> Option<&Seat> {
+        self.seats.get(seat)?.as_ref()
+    }
+
+    // Occupied seats with their index
+    pub fn seats(&self) -> impl Iterator<Item = (usize, &Seat)> + '_ {
+        self.seats.iter().enumerate().filter_map(|(i, seat)| Some((i, seat.as_ref()?)))
+    }
+
+    // May be an empty seat or one whose player busted, see blinds::next_positions
+    pub fn button(&self) -> Option<usize> {
+        self.positions.map(|positions| positions.button)
+    }
+
+    // Button and blind seats of the current or last hand
+    pub fn positions(&self) -> Option<Positions> {
+        self.positions
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    pub fn pot(&self) -> u32 {
+        self.seats().map(|(_, seat)| seat.contributed).sum()
+    }
+
+    // The main and side pots so far, and the part of the highest bet nobody matched yet
+    pub fn pots(&self) -> (Vec<Pot>, Option<(usize, u32)>) {
+        pot::build_pots(&self.contributions())
+    }
+
+    fn contributions(&self) -> Vec<Contribution> {
+        self.seats()
+            .filter(|(_, seat)| seat.contributed > 0)
+            .map(|(i, seat)| Contribution { seat: i, amount: seat.contributed, folded: seat.folded, dead: seat.dead })
+            .collect()
+    }
+
+    // Result of the last completed hand
+    pub fn result(&self) -> Option<&HandResult> {
+        self.result.as_ref()
+    }
+
+    // Events of the current or last hand
+    pub fn history(&self) -> Option<&HandHistory> {
+        self.history.as_ref()
+    }
+
+    pub fn is_hand_in_progress(&self) -> bool {
+        matches!(self.phase, Phase::Betting(_))
+    }
+
+    // A player joining after the first hand owes a big blind, unless they sit in the big blind
+    pub fn sit(&mut self, seat: usize, player: Player, stack: u32) -> Result<(), GameError> {
+        let joining = self.positions.is_some();
+
+        match self.seats.get_mut(seat) {
+            None => Err(GameError::InvalidSeat(seat)),
+            Some(Some(_)) => Err(GameError::SeatTaken(seat)),
+            Some(slot) => {
+                let mut new_seat = Seat::new(player, stack);
+                new_seat.missed_blinds.big_blind = joining;
+                *slot = Some(new_seat);
+                Ok(())
+            }
+        }
+    }
+
+    // Takes effect from the next hand
+    pub fn sit_out(&mut self, seat: usize) -> Result<(), GameError> {
+        self.occupied_mut(seat)?.sitting_out = true;
+        Ok(())
+    }
+
+    pub fn sit_in(&mut self, seat: usize) -> Result<(), GameError> {
+        self.occupied_mut(seat)?.sitting_out = false;
+        Ok(())
+    }
+
+    // Add chips to a stack between hands, for rebuys and top-ups
+    pub fn add_chips(&mut self, seat: usize, amount: u32) -> Result<(), GameError> {
+        if self.is_hand_in_progress() {
+            return Err(GameError::HandInProgress);
+        }
+
+        let stack = &mut self.occupied_mut(seat)?.stack;
+        *stack = stack.checked_add(amount).ok_or(GameError::StackOverflow { seat, amount })?;
+        Ok(())
+    }
+
+    pub fn leave(&mut self, seat: usize) -> Result<Seat, GameError> {
+        if self.is_hand_in_progress() {
+            return Err(GameError::HandInProgress);
+        }
+
+        self.seats.get_mut(seat).ok_or(GameError::InvalidSeat(seat))?.take().ok_or(GameError::SeatEmpty(seat))
+    }
+
+    /*
+        Move the button and blinds, post the antes, blinds and straddle and deal two hole
+        cards to every seated player with chips, starting left of the button. The player
+        left of the big blind, or of the straddle, acts first.
+    */
+    pub fn start_hand<R: Rng>(&mut self, rng: &mut R) -> Result<(), GameError> {
+        if self.is_hand_in_progress() {
+            return Err(GameError::HandInProgress);
+        }
+
+        let active: Vec<bool> = self.seats.iter().map(|seat| seat.as_ref().map(|seat| seat.stack > 0 && !seat.sitting_out).unwrap_or(false)).collect();
+        if active.iter().filter(|&&active| active).count() < MIN_SEATS {
+            return Err(GameError::NotEnoughPlayers);
+        }
+
+        for seat in self.seats.iter_mut().flatten() {
+            seat.hole_cards = None;
+            seat.bet = 0;
+            seat.contributed = 0;
+            seat.folded = false;
+            seat.acted = false;
+            seat.faced = 0;
+            seat.dead = 0;
+        }
+
+        let blinds = self.blinds;
+        let positions = blinds::next_positions(self.positions, &active, blinds.straddle.is_some());
+
+        // Players not dealt in miss the blinds which move past them
+        if let Some(previous) = self.positions {
+            let seat_count = self.seats.len();
+            let passed = |from: usize, to: usize, seat: usize| (seat + seat_count - from) % seat_count <= (to + seat_count - from) % seat_count && seat != from;
+
+            for (i, seat) in self.seats.iter_mut().enumerate() {
+                if let Some(seat) = seat.as_mut().filter(|_| !active[i]) {
+                    seat.missed_blinds.big_blind |= passed(previous.big_blind, positions.big_blind, i);
+                    seat.missed_blinds.small_blind |= passed(previous.small_blind, positions.small_blind, i);
+                }
+            }
+        }
+        self.positions = Some(positions);
+
+        self.hands += 1;
+        let players = self
+            .seats()
+            .filter(|&(i, _)| active[i])
+            .map(|(i, seat)| SeatRecord { seat: i, name: seat.player.name.clone(), stack: seat.stack })
+            .collect();
+        self.history = Some(HandHistory::new(self.hands, self.structure.name(), blinds, self.seats.len(), positions.button, players));
+
+        if let Ante::Each(ante) = blinds.ante {
+            for seat in (0..active.len()).filter(|&seat| active[seat]) {
+                self.post(seat, Post::Ante, ante);
+            }
+        }
+
+        // A dead small blind is not posted
+        if active[positions.small_blind] {
+            self.post(positions.small_blind, Post::SmallBlind, blinds.small_blind);
+        }
+        self.post(positions.big_blind, Post::BigBlind, blinds.big_blind);
+        if let Ante::BigBlind(ante) = blinds.ante {
+            self.post(positions.big_blind, Post::Ante, ante);
+        }
+
+        let mut last_blind = positions.big_blind;
+        self.current_bet = blinds.big_blind;
+        self.min_raise = self.structure.opening_size(Street::Preflop, blinds.big_blind);
+        self.raises = 1;
+
+        // The straddle is a blind raise, after which the straddler still has the option, unless all-in from the ante
+        let straddler = positions.straddle.filter(|&seat| self.seat(seat).unwrap().stack > 0);
+        if let (Some(seat), Some(straddle)) = (straddler, blinds.straddle) {
+            self.post(seat, Post::Straddle, straddle);
+            last_blind = seat;
+            self.current_bet = straddle;
+            self.min_raise = self.structure.opening_size(Street::Preflop, straddle);
+            self.raises = 2;
+        }
+
+        // Players dealt in again post the blinds they missed, except from the blinds themselves
+        for seat in (0..active.len()).filter(|&seat| active[seat]) {
+            let missed = self.seat(seat).unwrap().missed_blinds;
+            let in_blind = seat == positions.big_blind;
+
+            if missed.big_blind && !in_blind {
+                let bet = self.seat(seat).unwrap().bet;
+                self.post(seat, Post::MissedBigBlind, blinds.big_blind.saturating_sub(bet));
+            }
+            if missed.small_blind && !in_blind && seat != positions.small_blind {
+                self.post(seat, Post::MissedSmallBlind, blinds.small_blind);
+            }
+            self.seat_mut(seat).missed_blinds = MissedBlinds::default();
+        }
+
+        self.deck = Deck::new();
+        self.deck.shuffle_with(rng);
+        self.board.clear();
+        self.result = None;
+
+        let order: Vec<usize> = self.clockwise(positions.button).filter(|&seat| active[seat]).collect();
+
+        let first: Vec<Card> = order.iter().map(|_| self.deck.pop().unwrap()).collect();
+        for (&seat, first) in order.iter().zip(first) {
+            let second = self.deck.pop().unwrap();
+            self.seat_mut(seat).hole_cards = Some((first, second));
+            self.record(EventKind::Dealt { seat, cards: (first, second) });
+        }
+
+        self.phase = Phase::Betting(Street::Preflop);
+        self.to_act = self.next_to_act(last_blind);
+
+        if self.to_act.is_none() {
+            self.finish_street();
+        }
+
+        Ok(())
+    }
+
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        let seat_index = self.to_act?;
+        let seat = self.seat(seat_index)?;
+
+        let Phase::Betting(street) = self.phase else {
+            return None;
+        };
+
+        let check = seat.bet == self.current_bet;
+        let call = (seat.bet < self.current_bet).then(|| (self.current_bet - seat.bet).min(seat.stack));
+
+        /*
+            Raising is only possible while someone else can still call, and a player who
+            already acted may only raise again after a full raise, so an incomplete all-in
+            raise does not reopen the action. Several of them adding up to a full raise do.
+        */
+        let others_can_act = self.seats().any(|(i, other)| i != seat_index && other.can_act());
+        let reopened = !seat.acted || self.current_bet >= seat.faced + self.min_raise;
+
+        let context = BettingContext {
+            street,
+            big_blind: self.blinds.big_blind,
+            current_bet: self.current_bet,
+            min_raise: self.min_raise,
+            raises: self.raises,
+            pot: self.pot(),
+            bet: seat.bet,
+            stack: seat.stack,
+            players: self.seats().filter(|(_, seat)| seat.is_in_hand()).count(),
+        };
+
+        let sizes = self.structure.sizes(&context).filter(|_| others_can_act && reopened);
+        let (bet, raise) = match self.current_bet {
+            0 => (sizes, None),
+            _ => (None, sizes),
+        };
+
+        Some(LegalActions { seat: seat_index, check, call, bet, raise })
+    }
+
+    pub fn act(&mut self, action: Action) -> Result<(), GameError> {
+        let legal = self.legal_actions().ok_or(GameError::NoHandInProgress)?;
+        if !legal.allows(action) {
+            return Err(GameError::IllegalAction(action));
+        }
+
+        let current_bet = self.current_bet;
+        let seat = self.seat_mut(legal.seat);
+
+        let put_in = match action {
+            Action::Fold => {
+                seat.folded = true;
+                0
+            }
+            Action::Check => 0,
+            Action::Call => seat.put_in(current_bet - seat.bet),
+            Action::Bet(amount) | Action::Raise(amount) => {
+                let put_in = seat.put_in(amount - seat.bet);
+                self.current_bet = amount;
+
+                // Only a full bet or raise sets the minimum for the next one
+                if amount - current_bet >= self.min_raise {
+                    self.min_raise = amount - current_bet;
+                    self.raises += 1;
+                }
+
+                put_in
+            }
+        };
+
+        let current_bet = self.current_bet;
+        let seat = self.seat_mut(legal.seat);
+        seat.acted = true;
+        seat.faced = current_bet;
+        let all_in = put_in > 0 && seat.stack == 0;
+
+        if let Phase::Betting(street) = self.phase {
+            self.record(EventKind::Action { street, seat: legal.seat, action, amount: put_in, all_in });
+        }
+
+        if self.seats().filter(|(_, seat)| seat.is_in_hand()).count() == 1 {
+            self.showdown();
+            return Ok(());
+        }
+
+        self.to_act = self.next_to_act(legal.seat);
+        if self.to_act.is_none() {
+            self.finish_street();
+        }
+
+        Ok(())
+    }
+
+    pub fn view(&self, face_up: bool) -> TableView<'_> {
+        TableView {
+            seats: self
+                .seats()
+                .map(|(i, seat)| SeatView {
+                    name: &seat.player.name,
+                    stack: seat.stack,
+                    hole_cards: seat.hole_cards.filter(|_| !seat.folded),
+                    face_up,
+                    is_button: self.button() == Some(i),
+                })
+                .collect(),
+            board: &self.board,
+            pot: self.pot(),
+        }
+    }
+
+    // Post a blind, straddle or ante, recording the chips actually posted
+    fn post(&mut self, seat: usize, post: Post, amount: u32) {
+        let amount = match post {
+            Post::Ante | Post::MissedSmallBlind => self.seat_mut(seat).post_ante(amount),
+            _ => self.seat_mut(seat).put_in(amount),
+        };
+
+        // A big blind ante is paid for everyone and a missed small blind is dead, neither is a bet to be matched
+        let big_blind_ante = post == Post::Ante && matches!(self.blinds.ante, Ante::BigBlind(_));
+        if big_blind_ante || post == Post::MissedSmallBlind {
+            self.seat_mut(seat).dead += amount;
+        }
+
+        if amount > 0 {
+            self.record(EventKind::Post { seat, post, amount });
+        }
+    }
+
+    fn record(&mut self, kind: EventKind) {
+        if let Some(history) = self.history.as_mut() {
+            history.record(kind);
+        }
+    }
+
+    fn occupied_mut(&mut self, seat: usize) -> Result<&mut Seat, GameError> {
+        self.seats.get_mut(seat).ok_or(GameError::InvalidSeat(seat))?.as_mut().ok_or(GameError::SeatEmpty(seat))
+    }
+
+    fn seat_mut(&mut self, seat: usize) -> &mut Seat {
+        self.seats[seat].as_mut().unwrap()
+    }
+
+    // Seats in clockwise order, starting after the given one and ending with it
+    fn clockwise(&self, after: usize) -> impl Iterator<Item = usize> {
+        let seat_count = self.seats.len();
+        (1..=seat_count).map(move |offset| (after + offset) % seat_count)
+    }
+
+    // The next player who still has to act on this street, none once the betting round is over
+    fn next_to_act(&self, after: usize) -> Option<usize> {
+        let can_act = self.seats().filter(|(_, seat)| seat.can_act()).count();
+
+        self.clockwise(after).find(|&i| {
+            let Some(seat) = self.seat(i).filter(|seat| seat.can_act()) else {
+                return false;
+            };
+
+            // A lone player who can still act only has to match the bet
+            match can_act {
+                1 => seat.bet < self.current_bet,
+                _ => !seat.acted || seat.bet < self.current_bet,
+            }
+        })
+    }
+
+    // Deal the next street, or run out the board and show down once no more betting is possible
+    fn finish_street(&mut self) {
+        loop {
+            let Phase::Betting(street) = self.phase else {
+                return;
+            };
+
+            let next = match street {
+                Street::Preflop => Street::Flop,
+                Street::Flop => Street::Turn,
+                Street::Turn => Street::River,
+                Street::River => return self.showdown(),
+            };
+
+            // Burn a card before every street
+            self.deck.pop();
+            let dealt = self.board.len();
+            while self.board.len() < next.board_cards() {
+                self.board.push(self.deck.pop().unwrap());
+            }
+            let cards = self.board[dealt..].to_vec();
+            self.record(EventKind::Board { street: next, cards });
+
+            for seat in self.seats.iter_mut().flatten() {
+                seat.bet = 0;
+                seat.acted = false;
+                seat.faced = 0;
+            }
+
+            self.phase = Phase::Betting(next);
+            self.current_bet = 0;
+            self.min_raise = self.structure.opening_size(next, self.blinds.big_blind);
+            self.raises = 0;
+
+            if self.seats().filter(|(_, seat)| seat.can_act()).count() >= 2 {
+                // Heads-up the big blind acts first after the flop, as the button is the small blind
+                self.to_act = self.next_to_act(self.button().unwrap());
+                return;
+            }
+        }
+    }
+
+    // Show down the hands left, unless everyone else folded, and award the pots
+    fn showdown(&mut self) {
+        let in_hand: Vec<usize> = self.seats().filter(|(_, seat)| seat.is_in_hand()).map(|(i, _)| i).collect();
+
+        let shown: Vec<(usize, Strength)> = match in_hand.len() {
+            1 => Vec::new(),
+            _ => in_hand
+                .iter()
+                .map(|&i| (i, showdown_strength(self.seat(i).unwrap().hole_cards.unwrap(), &self.board)))
+                .collect(),
+        };
+
+        let pots = pot::award(&self.contributions(), &shown, self.button().unwrap(), self.seats.len());
+        let winnings = pots.winnings();
+
+        for (seat, strength) in &shown {
+            let cards = self.seat(*seat).unwrap().hole_cards.unwrap();
+            self.record(EventKind::Show { seat: *seat, cards, strength: strength.clone() });
+        }
+        if let Some((seat, amount)) = pots.uncalled {
+            self.record(EventKind::Uncalled { seat, amount });
+        }
+        for (pot, award) in pots.pots.iter().enumerate() {
+            for &(seat, amount) in &award.winners {
+                self.record(EventKind::Win { seat, pot, amount });
+            }
+        }
+
+        for &(seat, amount) in &winnings {
+            self.seat_mut(seat).stack += amount;
+        }
+
+        self.result = Some(HandResult {
+            board: self.board.clone(),
+            shown,
+            winnings,
+            pots,
+        });
+        self.phase = Phase::Complete;
+        self.to_act = None;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::betting::{FixedLimit, PotLimit};
+
+    fn table(stacks: &[u32]) -> Table {
+        let mut table = Table::new(stacks.len(), Blinds::new(5, 10)).unwrap();
+        for (seat, &stack) in stacks.iter().enumerate() {
+            table.sit(seat, Player::new(&format!("Player {seat}")), stack).unwrap();
+        }
+        table
+    }
+
+    fn fold_around(table: &mut Table) {
+        while table.legal_actions().is_some() {
+            table.act(Action::Fold).unwrap();
+        }
+    }
+
+    /*
+        Complete hands of every betting structure played with random actions at new tables of
+        random players, with antes, big blind antes and straddles, and short stacks for side pots.
+    */
+    pub(crate) fn played_hands(seed: u64, count: usize) -> Vec<HandHistory> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..count)
+            .map(|hand| {
+                let ante = match hand % 3 {
+                    0 => Ante::None,
+                    1 => Ante::Each(2),
+                    _ => Ante::BigBlind(10),
+                };
+                let straddle = (hand % 4 == 3).then_some(20);
+
+                let mut table = Table::new(6, Blinds { ante, straddle, ..Blinds::new(5, 10) }).unwrap();
+                table.structure = match hand % 3 {
+                    0 => Box::new(NoLimit),
+                    1 => Box::new(PotLimit),
+                    _ => Box::new(FixedLimit::new(10)),
+                };
+                for seat in 0..6 {
+                    if seat < 2 || rng.gen_bool(0.7) {
+                        table.sit(seat, Player::new(&format!("Player {seat}")), rng.gen_range(15..400)).unwrap();
+                    }
+                }
+
+                table.start_hand(&mut rng).unwrap();
+                while let Some(legal) = table.legal_actions() {
+                    let action = match (rng.gen_range(0..6), legal.bet, legal.raise) {
+                        (0, _, _) if !legal.check => Action::Fold,
+                        (1 | 2, Some((min, max)), _) => Action::Bet(rng.gen_range(min..=max)),
+                        (1 | 2, _, Some((min, max))) => Action::Raise(rng.gen_range(min..=max)),
+                        _ if legal.check => Action::Check,
+                        _ => Action::Call,
+                    };
+                    table.act(action).unwrap();
+                }
+
+                table.history().unwrap().clone()
+            })
+            .collect()
+    }
+
+    // The history as read back from a hand history format: times to the second and the blinds as posted
+    pub(crate) fn as_written(history: &HandHistory) -> HandHistory {
+        let mut history = history.clone();
+        history.blinds = history.posted_blinds();
+
+        let seconds = history.started.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        history.started = std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+        for event in history.events.iter_mut() {
+            event.time = history.started;
+        }
+
+        history
+    }
+
+    #[test]
+    fn incomplete_all_in_raise_does_not_reopen_the_action() {
+        // Seat 0 has the button, seat 1 the small blind, seat 2 the big blind
+        let mut table = table(&[45, 1000, 1000, 1000]);
+        table.start_hand(&mut StdRng::seed_from_u64(1)).unwrap();
+
+        assert_eq!(table.legal_actions().unwrap().raise, Some((20, 1000)));
+        table.act(Action::Raise(30)).unwrap();
+
+        // Short of the minimum raise to 50, the button may only go all-in
+        assert_eq!(table.legal_actions().unwrap().raise, Some((45, 45)));
+        table.act(Action::Raise(45)).unwrap();
+        table.act(Action::Fold).unwrap();
+
+        // The big blind has not acted yet and may raise by the last full raise of 20
+        let legal = table.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call, legal.raise), (2, Some(35), Some((65, 1000))));
+        table.act(Action::Call).unwrap();
+
+        // The first raiser only faced an incomplete raise since acting
+        let legal = table.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.call, legal.raise), (3, Some(15), None));
+        assert_eq!(table.act(Action::Raise(100)), Err(GameError::IllegalAction(Action::Raise(100))));
+    }
+
+    #[test]
+    fn full_raise_by_an_all_in_reopens_the_action() {
+        let mut table = table(&[60, 1000, 1000, 1000]);
+        table.start_hand(&mut StdRng::seed_from_u64(1)).unwrap();
+
+        table.act(Action::Raise(30)).unwrap();
+        table.act(Action::Raise(60)).unwrap();
+        table.act(Action::Call).unwrap();
+        table.act(Action::Fold).unwrap();
+
+        let legal = table.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.raise), (3, Some((90, 1000))));
+    }
+
+    #[test]
+    fn missed_blinds_are_posted_live_and_dead() {
+        let mut table = table(&[1000, 1000, 1000, 1000]);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Seat 3 sits out while the big blind and then the small blind pass it
+        table.start_hand(&mut rng).unwrap();
+        fold_around(&mut table);
+        table.sit_out(3).unwrap();
+        for _ in 0..2 {
+            table.start_hand(&mut rng).unwrap();
+            fold_around(&mut table);
+        }
+        table.sit_in(3).unwrap();
+
+        table.start_hand(&mut rng).unwrap();
+        assert_eq!(table.positions().unwrap().big_blind, 2);
+
+        let events = &table.history().unwrap().events;
+        let posts: Vec<EventKind> = events.iter().map(|event| event.kind.clone()).filter(|kind| matches!(kind, EventKind::Post { seat: 3, .. })).collect();
+        assert_eq!(
+            posts,
+            [
+                EventKind::Post { seat: 3, post: Post::MissedBigBlind, amount: 10 },
+                EventKind::Post { seat: 3, post: Post::MissedSmallBlind, amount: 5 },
+            ]
+        );
+
+        // The live big blind counts as a call, the dead small blind goes to the winner
+        let legal = table.legal_actions().unwrap();
+        assert!(legal.seat == 3 && legal.check);
+        fold_around(&mut table);
+
+        assert_eq!(table.result().unwrap().winnings, [(2, 30)]);
+        assert_eq!(table.seat(3).unwrap().stack, 985);
+    }
+
+    #[test]
+    fn side_pots_are_awarded_to_the_eligible_seats() {
+        for history in played_hands(7, 200) {
+            let put_in: u32 = history.seats.iter().map(|record| history.invested(record.seat)).sum();
+            let collected: u32 = history.seats.iter().map(|record| history.collected(record.seat)).sum();
+            assert_eq!(put_in, collected, "hand {}", history.number);
+
+            // Nobody wins a pot after folding
+            for event in &history.events {
+                if let EventKind::Win { seat, .. } = event.kind {
+                    assert!(!history.events.iter().any(|other| matches!(other.kind, EventKind::Action { seat: folder, action: Action::Fold, .. } if folder == seat)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// https://en.wikipedia.org/wiki/Poker_tournament
+// https://en.wikipedia.org/wiki/Sit_and_go
+
+use std::{
+    cmp::Reverse,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::{
+    blinds::{Ante, Blinds},
+    icm::{self, IcmError, Method},
+    table::{Action, GameError, Phase, Player, Table, MAX_SEATS, MIN_SEATS},
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TournamentError {
+    Entrants(usize),
+    NoLevels,
+    Payouts,
+    Finished,
+    Game(GameError),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::Entrants(count) => write!(f, "tournament has {count} entrants, expected {MIN_SEATS} to {MAX_SEATS}"),
+            TournamentError::NoLevels => write!(f, "blind schedule has no levels"),
+            TournamentError::Payouts => write!(f, "payouts must be fractions of the prize pool adding up to at most 1, for at most one place per entrant"),
+            TournamentError::Finished => write!(f, "tournament is finished"),
+            TournamentError::Game(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+impl From<GameError> for TournamentError {
+    fn from(error: GameError) -> Self {
+        TournamentError::Game(error)
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum LevelLength {
+    Hands(u32),
+    Time(Duration),
+}
+
+// Blinds and antes by level, which go up after a number of hands or a length of time
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Schedule {
+    pub levels: Vec<Blinds>,
+    pub length: LevelLength,
+}
+
+impl Schedule {
+    // A typical sit & go structure for starting stacks of 1500, with antes from the sixth level
+    pub fn sit_and_go(length: LevelLength) -> Self {
+        let blinds = [
+            (10, 20), (15, 30), (25, 50), (50, 100), (75, 150), (100, 200), (150, 300),
+            (200, 400), (300, 600), (400, 800), (600, 1200), (800, 1600), (1000, 2000),
+        ];
+
+        Self {
+            levels: blinds
+                .iter()
+                .enumerate()
+                .map(|(level, &(small_blind, big_blind))| Blinds {
+                    ante: if level >= 5 { Ante::Each(big_blind / 8) } else { Ante::None },
+                    ..Blinds::new(small_blind, big_blind)
+                })
+                .collect(),
+            length,
+        }
+    }
+
+    // Index of the level after the given hands and time played, the last level lasts until the end
+    pub fn level(&self, hands: u32, elapsed: Duration) -> usize {
+        let level = match self.length {
+            LevelLength::Hands(length) => (hands / length.max(1)) as usize,
+            LevelLength::Time(length) => (elapsed.as_nanos() / length.as_nanos().max(1)) as usize,
+        };
+
+        level.min(self.levels.len().saturating_sub(1))
+    }
+}
+
+// The usual sit & go split of the prize pool, first place first
+pub fn standard_payouts(entrants: usize) -> Vec<f64> {
+    match entrants {
+        0..=3 => vec![1.],
+        4..=6 => vec![0.65, 0.35],
+        _ => vec![0.5, 0.3, 0.2],
+    }
+}
+
+// Prize money by place, rounded down with the remainder going to first place
+pub fn prizes(prize_pool: u32, payouts: &[f64]) -> Vec<u32> {
+    let mut prizes: Vec<u32> = payouts.iter().map(|payout| (prize_pool as f64 * payout).floor() as u32).collect();
+
+    if let Some(first) = prizes.first_mut() {
+        let paid: u32 = payouts.iter().map(|payout| (prize_pool as f64 * payout).floor() as u32).sum();
+        let total = (prize_pool as f64 * payouts.iter().sum::<f64>()).round() as u32;
+        *first += total.saturating_sub(paid);
+    }
+
+    prizes
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct TournamentConfig {
+    pub starting_stack: u32,
+    pub schedule: Schedule,
+    pub prize_pool: u32,
+    // Fractions of the prize pool by place, first place first
+    pub payouts: Vec<f64>,
+}
+
+// How a player finished, players busting in the same hand with equal stacks share a place
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Finish {
+    pub seat: usize,
+    pub name: String,
+    pub place: usize,
+    // Number of the hand in which the player busted or won, starting at 1
+    pub hand: u32,
+    pub prize: u32,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Standing {
+    pub place: usize,
+    pub name: String,
+    pub chips: u32,
+    // Prize won, none while the player is still in the tournament
+    pub prize: Option<u32>,
+}
+
+/*
+    A single table tournament, which plays hands at the blinds of the current level and
+    eliminates players who lose all their chips, until one player has all of them. Players
+    are seated in the order given, the prize pool is paid out by finishing place.
+*/
+#[derive(Debug)]
+pub struct Tournament {
+    table: Table,
+    schedule: Schedule,
+    prizes: Vec<u32>,
+    entrants: usize,
+    hands: u32,
+    level: usize,
+    started: Instant,
+    // Stacks at the start of the current hand, to rank players busting in the same hand
+    starting_stacks: Vec<(usize, u32)>,
+    // In order of elimination, the winner last
+    finishes: Vec<Finish>,
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig, players: &[&str]) -> Result<Self, TournamentError> {
+        let entrants = players.len();
+        if !(MIN_SEATS..=MAX_SEATS).contains(&entrants) {
+            return Err(TournamentError::Entrants(entrants));
+        }
+
+        let Some(&blinds) = config.schedule.levels.first() else {
+            return Err(TournamentError::NoLevels);
+        };
+
+        let valid = config.payouts.iter().all(|payout| payout.is_finite() && *payout >= 0.);
+        if !valid || config.payouts.len() > entrants || config.payouts.iter().sum::<f64>() > 1. + 1e-9 {
+            return Err(TournamentError::Payouts);
+        }
+
+        let mut table = Table::new(entrants, blinds)?;
+        for (seat, name) in players.iter().enumerate() {
+            table.sit(seat, Player::new(name), config.starting_stack)?;
+        }
+
+        Ok(Self {
+            table,
+            prizes: prizes(config.prize_pool, &config.payouts),
+            schedule: config.schedule,
+            entrants,
+            hands: 0,
+            level: 0,
+            started: Instant::now(),
+            starting_stacks: Vec::new(),
+            finishes: Vec::new(),
+        })
+    }
+
+    // The table, for legal actions and the state of the current hand
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn blinds(&self) -> Blinds {
+        self.schedule.levels[self.level]
+    }
+
+    pub fn hands_played(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // Prize money by place, first place first
+    pub fn prizes(&self) -> &[u32] {
+        &self.prizes
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finishes.len() == self.entrants
+    }
+
+    // Players who finished so far, in order of elimination with the winner last
+    pub fn finishes(&self) -> &[Finish] {
+        &self.finishes
+    }
+
+    // Deal the next hand at the blinds of the level reached by now
+    pub fn start_hand<R: Rng>(&mut self, rng: &mut R) -> Result<(), TournamentError> {
+        if self.is_finished() {
+            return Err(TournamentError::Finished);
+        }
+
+        if self.table.is_hand_in_progress() {
+            return Err(GameError::HandInProgress.into());
+        }
+
+        self.level = self.schedule.level(self.hands, self.started.elapsed());
+        self.table.blinds = self.blinds();
+        self.starting_stacks = self.table.seats().map(|(i, seat)| (i, seat.stack)).collect();
+
+        self.table.start_hand(rng)?;
+        self.check_hand_over();
+
+        Ok(())
+    }
+
+    pub fn act(&mut self, action: Action) -> Result<(), TournamentError> {
+        if self.is_finished() {
+            return Err(TournamentError::Finished);
+        }
+
+        self.table.act(action)?;
+        self.check_hand_over();
+
+        Ok(())
+    }
+
+    // Players still in by chips, followed by those who finished by place
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut remaining: Vec<Standing> = self
+            .table
+            .seats()
+            .filter(|(i, _)| !self.finishes.iter().any(|finish| finish.seat == *i))
+            .map(|(_, seat)| Standing { place: 0, name: seat.player.name.clone(), chips: seat.stack, prize: None })
+            .collect();
+        remaining.sort_by_key(|standing| Reverse(standing.chips));
+
+        for (i, standing) in remaining.iter_mut().enumerate() {
+            standing.place = i + 1;
+        }
+
+        let mut finished: Vec<Standing> = self
+            .finishes
+            .iter()
+            .map(|finish| Standing {
+                place: finish.place,
+                name: finish.name.clone(),
+                chips: self.table.seat(finish.seat).map(|seat| seat.stack).unwrap_or(0),
+                prize: Some(finish.prize),
+            })
+            .collect();
+        finished.sort_by_key(|standing| standing.place);
+
+        remaining.into_iter().chain(finished).collect()
+    }
+
+    // Prize money equity of the players still in by seat, with the prizes of the places left
+    pub fn icm_equities(&self, method: Method) -> Result<Vec<(usize, f64)>, IcmError> {
+        let seats: Vec<(usize, u32)> = self.table.seats().map(|(i, seat)| (i, seat.stack)).collect();
+        let stacks: Vec<u32> = seats.iter().map(|&(_, stack)| stack).collect();
+        let payouts: Vec<f64> = self.prizes.iter().take(seats.len()).map(|&prize| prize as f64).collect();
+
+        let result = icm::equities(&stacks, &payouts, method)?;
+        Ok(seats.iter().map(|&(seat, _)| seat).zip(result.equities).collect())
+    }
+
+    fn check_hand_over(&mut self) {
+        if self.table.phase() != Phase::Complete {
+            return;
+        }
+
+        self.hands += 1;
+
+        // Of the players busting together, the one who started the hand with more chips finishes higher
+        let mut busted: Vec<(usize, u32)> = self
+            .starting_stacks
+            .iter()
+            .copied()
+            .filter(|&(seat, _)| self.table.seat(seat).map(|seat| seat.stack == 0).unwrap_or(false))
+            .collect();
+        busted.sort_by_key(|&(seat, stack)| (stack, seat));
+
+        let remaining = self.table.seats().count() - busted.len();
+        let mut next_place = self.entrants - self.finishes.len();
+
+        for group in busted.chunk_by(|a, b| a.1 == b.1) {
+            let place = next_place + 1 - group.len();
+            let shared: u32 = (place..place + group.len()).map(|place| self.prize(place)).sum();
+
+            for (i, &(seat, _)) in group.iter().enumerate() {
+                let player = self.table.leave(seat).unwrap().player;
+                let share = shared / group.len() as u32 + (i < shared as usize % group.len()) as u32;
+                self.finishes.push(Finish { seat, name: player.name, place, hand: self.hands, prize: share });
+            }
+
+            next_place = place - 1;
+        }
+
+        if remaining == 1 {
+            let (seat, winner) = self.table.seats().next().unwrap();
+            self.finishes.push(Finish { seat, name: winner.player.name.clone(), place: 1, hand: self.hands, prize: self.prize(1) });
+        }
+    }
+
+    fn prize(&self, place: usize) -> u32 {
+        self.prizes.get(place - 1).copied().unwrap_or(0)
+    }
+}